@@ -15,6 +15,7 @@ use tokio::time::sleep;
 
 const SYSTEM_PROMPT_TEMPLATE: &str = include_str!("../data/prompt.md");
 const SYSTEM_PROMPT_TEMPLATE_AVAILABLE_MCP_TOOL_MARK: &str = "{AVAILABLE_MCP_TOOL_PROMPT}";
+const SYSTEM_PROMPT_TEMPLATE_AVAILABLE_SOURCE_MARK: &str = "{AVAILABLE_SOURCE_PROMPT}";
 
 const AGENT_MODEL: &str = "claude-sonnet-4-20250514";
 const AGENT_TEMPERATURE: f64 = 0.8;
@@ -27,7 +28,7 @@ pub struct QueryInferenceAgent {
 
 impl QueryInferenceAgent {
     pub fn new(ctx: Rc<Context>, config: &Config, mcp_tools: Vec<McpTool>) -> QueryInferenceAgent {
-        let system_prompt = Self::build_system_prompt(mcp_tools);
+        let system_prompt = Self::build_system_prompt(config, mcp_tools);
         info!("SYSTEM_PROMPT: {}", system_prompt);
 
         let client = anthropic::ClientBuilder::new(config.claude_token.as_str()).build();
@@ -47,9 +48,16 @@ impl QueryInferenceAgent {
         }
     }
 
-    fn build_system_prompt(mcp_tools: Vec<McpTool>) -> String {
+    fn build_system_prompt(config: &Config, mcp_tools: Vec<McpTool>) -> String {
         let tool_prompt = mcp_tools
             .iter()
+            .filter(|tool| {
+                config
+                    .mcp_servers
+                    .iter()
+                    .find(|server| server.name == tool.server_name)
+                    .is_some_and(|server| server.is_tool_allowed(&tool.tool_name))
+            })
             .map(|tool| {
                 let desc_text = if tool.description.contains("\n") {
                     format!("<desc>{}</desc>", tool.description)
@@ -67,10 +75,31 @@ Description: {}"#,
             .collect::<Vec<_>>()
             .join("\n");
 
-        let system_prompt = SYSTEM_PROMPT_TEMPLATE.replace(
-            SYSTEM_PROMPT_TEMPLATE_AVAILABLE_MCP_TOOL_MARK,
-            tool_prompt.as_str(),
-        );
+        let source_prompt = config
+            .sources
+            .iter()
+            .map(|source| {
+                format!(
+                    r#"- Table name: {}
+Format: {}
+Description: {}"#,
+                    source.name,
+                    source.format.to_lowercase(),
+                    source.description.as_deref().unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let system_prompt = SYSTEM_PROMPT_TEMPLATE
+            .replace(
+                SYSTEM_PROMPT_TEMPLATE_AVAILABLE_MCP_TOOL_MARK,
+                tool_prompt.as_str(),
+            )
+            .replace(
+                SYSTEM_PROMPT_TEMPLATE_AVAILABLE_SOURCE_MARK,
+                source_prompt.as_str(),
+            );
 
         system_prompt
     }
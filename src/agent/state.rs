@@ -2,6 +2,7 @@ use crate::agent::agent_error::AgentError;
 use crate::agent::response::Response;
 use crate::engine::context::Context;
 use crate::infra::rig_agent::RigAgent;
+use crate::model::engine::query_source::QuerySource;
 use crate::util::arrow::json::convert_to_json;
 use datafusion::arrow::record_batch::RecordBatch;
 use std::rc::Rc;
@@ -49,16 +50,19 @@ impl RunQueryState {
     }
 
     pub async fn run(&self, ctx: Rc<Context>) -> Result<Response, AgentError> {
-        let df = ctx.run_sql(&self.query).await?;
-        let batch = df.collect().await?;
+        let result = ctx.execute_sql(&self.query, QuerySource::Agent).await?;
 
-        let resp_json_u8 = convert_to_json(&batch)
+        let resp_json_u8 = convert_to_json(&result.batches)
             .await
             .map_err(|e| AgentError::new_unexpected_error(Box::new(e)))?;
         let resp_json = String::from_utf8(resp_json_u8)
             .map_err(|e| AgentError::new_unexpected_error(Box::new(e)))?;
 
-        let next_action = ChatState::new(format!("Result: {}", resp_json));
+        let message = match result.notice {
+            Some(notice) => format!("Result: {}\n{}", resp_json, notice),
+            None => format!("Result: {}", resp_json),
+        };
+        let next_action = ChatState::new(message);
         let response = Response::new(resp_json, State::Chat(next_action), vec![]);
         Ok(response)
     }
@@ -116,8 +120,7 @@ impl AttemptCompletionState {
     }
 
     pub async fn preview_query(&self, ctx: Rc<Context>) -> Result<Vec<RecordBatch>, AgentError> {
-        let df = ctx.run_sql(&self.query).await?;
-        let batch = df.limit(0, Some(5))?.collect().await?;
+        let batch = ctx.preview_sql(&self.query, QuerySource::Agent, 5).await?;
 
         Ok(batch)
     }
@@ -3,4 +3,5 @@ pub mod helper;
 pub mod input_validator;
 mod mcp_server;
 pub mod repl;
+mod tool_call_confirmer;
 mod ui;
@@ -14,6 +14,21 @@ pub struct Cli {
 
     #[arg(short, long, default_value = DEFAULT_CONFIG_FILE, global = true)]
     config: String,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "read_write",
+        help = "Reject DDL and DML statements"
+    )]
+    read_only: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Allow DDL and DML statements even for `serve mcp`"
+    )]
+    read_write: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -40,7 +55,13 @@ pub(super) enum ServeCommands {
 pub async fn run_cli() -> Result<(), Box<dyn Error>> {
     let cli = Cli::parse();
 
-    let config = Config::load_config(cli.config.as_str()).await?;
+    let mut config = Config::load_config(cli.config.as_str()).await?;
+    if cli.read_only {
+        config.access.read_only = Some(true);
+    } else if cli.read_write {
+        config.access.read_only = Some(false);
+    }
+
     match cli.command {
         Some(Commands::Repl) => run_repl(config).await,
         Some(Commands::Serve(args)) => match args.command {
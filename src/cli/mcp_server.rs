@@ -1,20 +1,27 @@
 use crate::config::config::Config;
 use crate::engine::context::Context;
 use crate::server::mcp::server::McpServer;
-use datafusion::common::Result;
+use datafusion::common::{Result, exec_err};
 use rmcp::service::QuitReason;
 use rmcp::{ServiceExt, transport::stdio};
 use std::sync::Arc;
 
-pub(super) async fn run_mcp_server(config: Config) -> Result<()> {
+pub(super) async fn run_mcp_server(mut config: Config) -> Result<()> {
     println!("DataHarpoon MCP Server running on stdio");
 
-    let ctx = Context::new(config);
-    let service = McpServer::new(Arc::new(ctx)).serve(stdio()).await?;
+    // Statements come from the client LLM, so only queries are allowed unless configured.
+    config.access.read_only.get_or_insert(true);
+
+    let ctx = Context::new(config).await;
+    let service = match McpServer::new(Arc::new(ctx)).serve(stdio()).await {
+        Ok(service) => service,
+        Err(e) => return exec_err!("failed to start mcp server. {}", e),
+    };
 
     match service.waiting().await {
         Ok(QuitReason::Closed) => println!("Closed"),
         Ok(QuitReason::Cancelled) => println!("Cancelled"),
+        Ok(QuitReason::JoinError(e)) => eprintln!("Error occurred. {:?}", e),
         Err(e) => eprintln!("Error occurred. {:?}", e),
     }
 
@@ -2,18 +2,21 @@ use crate::agent::agent_error::AgentError;
 use crate::agent::query_inference_agent::QueryInferenceAgent;
 use crate::cli::helper::split_to_sqls;
 use crate::cli::input_validator::ReplValidator;
+use crate::cli::tool_call_confirmer::StdinToolCallConfirmer;
 use crate::cli::ui::display_content;
 use crate::config::config::Config;
 use crate::engine::context::Context;
+use crate::model::engine::query_source::QuerySource;
 use crate::model::ui::display_text::DisplayContent;
 use crate::repo::mcp_repo::McpRepo;
+use datafusion::arrow::util::pretty;
 use reedline::{DefaultPrompt, DefaultPromptSegment, Reedline, Signal};
 use regex::Regex;
 use std::error::Error;
 use std::io;
 use std::io::Write;
 use std::rc::Rc;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 use std::time::Duration;
 
 static ASK_COMMAND_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/ask\s+(\S.+)").unwrap());
@@ -26,7 +29,11 @@ pub async fn run_repl(config: Config) {
         DefaultPromptSegment::Empty,
     );
 
-    let ctx = Rc::new(Context::new(config.clone()));
+    let ctx = Rc::new(
+        Context::new(config.clone())
+            .await
+            .with_tool_call_confirmer(Arc::new(StdinToolCallConfirmer {})),
+    );
 
     loop {
         flush_stdout().await;
@@ -56,12 +63,15 @@ pub async fn run_repl(config: Config) {
                     }
                 } else if let Ok(sqls) = split_to_sqls(text.clone()) {
                     for sql in sqls {
-                        let res = ctx.run_sql(sql.as_str()).await;
+                        let res = ctx.execute_sql(sql.as_str(), QuerySource::Repl).await;
                         match res {
-                            Ok(df) => {
-                                if let Err(e) = df.show().await {
+                            Ok(result) => {
+                                if let Err(e) = pretty::print_batches(&result.batches) {
                                     handle_error(&e)
                                 }
+                                if let Some(notice) = result.notice {
+                                    println!("{}", notice);
+                                }
                             }
                             Err(e) => handle_error(&e),
                         }
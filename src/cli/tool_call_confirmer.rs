@@ -0,0 +1,95 @@
+use crate::engine::tool_policy::ToolCallConfirmer;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use rmcp::model::ToolAnnotations;
+use std::io;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+#[derive(Debug)]
+pub(super) struct StdinToolCallConfirmer {}
+
+impl ToolCallConfirmer for StdinToolCallConfirmer {
+    fn confirm(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        annotations: Option<&ToolAnnotations>,
+        cancelled: &AtomicBool,
+    ) -> bool {
+        let destructive = annotations.and_then(|a| a.destructive_hint).unwrap_or(true);
+        print!(
+            "\ntool({}) of mcp-server({}) is not read-only{}. Call it? [y/N] ",
+            tool_name,
+            server_name,
+            if destructive {
+                " and may be destructive"
+            } else {
+                ""
+            }
+        );
+        io::stdout().flush().ok();
+
+        let answer = match read_answer(cancelled) {
+            Some(answer) => answer,
+            None => return false,
+        };
+
+        matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+}
+
+/// Reads a line from the terminal, giving up once `cancelled` is set so that no read is left
+/// behind to take the next input of the REPL.
+fn read_answer(cancelled: &AtomicBool) -> Option<String> {
+    if terminal::enable_raw_mode().is_err() {
+        // Not a terminal, such as piped input, which can't be polled for keys.
+        let mut answer = String::new();
+        return io::stdin().read_line(&mut answer).ok().map(|_| answer);
+    }
+
+    let answer = read_keys(cancelled);
+    terminal::disable_raw_mode().ok();
+    print!("\r\n");
+    if answer.is_none() && cancelled.load(Ordering::Relaxed) {
+        print!("cancelled\r\n");
+    }
+    io::stdout().flush().ok();
+
+    answer
+}
+
+fn read_keys(cancelled: &AtomicBool) -> Option<String> {
+    let mut answer = String::new();
+    while !cancelled.load(Ordering::Relaxed) {
+        if !event::poll(Duration::from_millis(100)).ok()? {
+            continue;
+        }
+        let Event::Key(key) = event::read().ok()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Enter => return Some(answer),
+            KeyCode::Esc => return None,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return None,
+            KeyCode::Char(c) => {
+                answer.push(c);
+                print!("{}", c);
+            }
+            KeyCode::Backspace => {
+                if answer.pop().is_some() {
+                    print!("\u{8} \u{8}");
+                }
+            }
+            _ => continue,
+        }
+        io::stdout().flush().ok();
+    }
+
+    None
+}
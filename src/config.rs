@@ -1,2 +1,14 @@
+pub mod access_config;
+pub mod cache_config;
+pub mod catalog_config;
 pub mod config;
+pub mod limits_config;
+pub mod materialized_view_config;
+pub mod mcp_call_log_config;
 pub mod mcp_server_config;
+pub mod mcp_sink_config;
+pub mod query_history_config;
+pub mod rate_limit_config;
+pub mod source_config;
+pub mod sqlite_config;
+pub mod tool_policy_config;
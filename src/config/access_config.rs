@@ -0,0 +1,17 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct AccessConfig {
+    /// Local directories SQL can read from or write to. Unrestricted when omitted.
+    #[serde(default)]
+    pub allowed_roots: Option<Vec<String>>,
+    /// URL schemes (e.g. "file", "s3") SQL can access. Unrestricted when omitted.
+    #[serde(default)]
+    pub allowed_schemes: Option<Vec<String>>,
+    /// Rejects DDL and DML. Defaults to true for `serve mcp` and false for the REPL.
+    #[serde(default)]
+    pub read_only: Option<bool>,
+    /// Local directories `COPY ... TO` can still write to in read-only mode.
+    #[serde(default)]
+    pub writable_roots: Vec<String>,
+}
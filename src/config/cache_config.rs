@@ -0,0 +1,24 @@
+use crate::util::path::expand_home;
+use serde::Deserialize;
+use std::env;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct CacheConfig {
+    /// Directory for the snapshots of materialized views. Defaults to `~/.dataharpoon/cache`.
+    #[serde(default)]
+    pub dir: Option<String>,
+}
+
+impl CacheConfig {
+    pub fn cache_dir(&self) -> PathBuf {
+        if let Some(dir) = &self.dir {
+            return expand_home(dir);
+        }
+
+        match env::var("HOME") {
+            Ok(home) => PathBuf::from(home).join(".dataharpoon").join("cache"),
+            Err(_) => env::temp_dir().join("dataharpoon").join("cache"),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use crate::util::path::expand_home;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct CatalogConfig {
+    /// JSON file to store `CREATE VIEW` and `CREATE EXTERNAL TABLE` definitions so that they are
+    /// restored at the next start. Nothing is stored when not set.
+    #[serde(default)]
+    pub file: Option<String>,
+}
+
+impl CatalogConfig {
+    pub fn catalog_file(&self) -> Option<PathBuf> {
+        self.file.as_deref().map(expand_home)
+    }
+}
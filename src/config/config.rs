@@ -1,4 +1,15 @@
+use crate::config::access_config::AccessConfig;
+use crate::config::cache_config::CacheConfig;
+use crate::config::catalog_config::CatalogConfig;
+use crate::config::limits_config::LimitsConfig;
+use crate::config::materialized_view_config::MaterializedViewConfig;
+use crate::config::mcp_call_log_config::McpCallLogConfig;
 use crate::config::mcp_server_config::McpServerConfig;
+use crate::config::mcp_sink_config::McpSinkConfig;
+use crate::config::query_history_config::QueryHistoryConfig;
+use crate::config::source_config::SourceConfig;
+use crate::config::sqlite_config::SqliteConfig;
+use crate::config::tool_policy_config::ToolPolicyConfig;
 use regex::Regex;
 use serde::Deserialize;
 use std::error::Error;
@@ -8,12 +19,41 @@ use std::{env, fs};
 pub struct Config {
     #[serde(default)]
     pub mcp_servers: Vec<McpServerConfig>,
+    #[serde(default)]
+    pub sources: Vec<SourceConfig>,
+    #[serde(default)]
+    pub sqlite: Vec<SqliteConfig>,
+    #[serde(default)]
+    pub materialized_views: Vec<MaterializedViewConfig>,
+    #[serde(default)]
+    pub mcp_sinks: Vec<McpSinkConfig>,
     #[serde(default = "default_max_prompt_count")]
     pub max_prompt_count: usize,
 
     #[serde(default)]
     pub claude_token: String,
 
+    #[serde(default)]
+    pub mcp_call_log: McpCallLogConfig,
+
+    #[serde(default)]
+    pub query_history: QueryHistoryConfig,
+
+    #[serde(default)]
+    pub tool_policy: ToolPolicyConfig,
+
+    #[serde(default)]
+    pub access: AccessConfig,
+
+    #[serde(default)]
+    pub limits: LimitsConfig,
+
+    #[serde(default)]
+    pub catalog: CatalogConfig,
+
+    #[serde(default)]
+    pub cache: CacheConfig,
+
     #[serde(default)]
     pub dev: DevConfig,
 }
@@ -22,8 +62,19 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             mcp_servers: Vec::new(),
+            sources: Vec::new(),
+            sqlite: Vec::new(),
+            materialized_views: Vec::new(),
+            mcp_sinks: Vec::new(),
             max_prompt_count: default_max_prompt_count(),
             claude_token: "".to_string(),
+            mcp_call_log: McpCallLogConfig::default(),
+            query_history: QueryHistoryConfig::default(),
+            tool_policy: ToolPolicyConfig::default(),
+            access: AccessConfig::default(),
+            limits: LimitsConfig::default(),
+            catalog: CatalogConfig::default(),
+            cache: CacheConfig::default(),
             dev: DevConfig::default(),
         }
     }
@@ -71,6 +122,9 @@ impl Config {
             .to_string();
 
         let config = toml::from_str::<Config>(&content)?;
+        for source in config.sources.iter() {
+            source.validate()?;
+        }
 
         Ok(config)
     }
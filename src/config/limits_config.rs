@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct LimitsConfig {
+    /// Memory pool size for query execution. Operators spill to disk beyond it.
+    #[serde(default)]
+    pub memory_limit_mb: Option<usize>,
+    /// Directory for spill files. Defaults to the OS temporary directory.
+    #[serde(default)]
+    pub spill_dir: Option<String>,
+    #[serde(default)]
+    pub statement_timeout_secs: Option<u64>,
+    /// Results beyond these limits are truncated with a notice.
+    #[serde(default)]
+    pub max_result_rows: Option<usize>,
+    #[serde(default)]
+    pub max_result_bytes: Option<usize>,
+    /// Values mcp_agg sends to a call. Defaults to 1000 values and 1 MiB of JSON.
+    #[serde(default)]
+    pub mcp_agg_max_items: Option<usize>,
+    #[serde(default)]
+    pub mcp_agg_max_bytes: Option<usize>,
+    /// Calls to MCP servers a statement can make. `SET dataharpoon.max_mcp_calls` overrides it.
+    #[serde(default)]
+    pub max_mcp_calls: Option<usize>,
+}
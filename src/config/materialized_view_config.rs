@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// A query whose result is snapshotted to Parquet and read as a table.
+#[derive(Debug, Deserialize, Clone)]
+pub struct MaterializedViewConfig {
+    pub name: String,
+    /// e.g. "SELECT * FROM call_mcp('github', 'list_issues', {'repo': 'awesome'})"
+    pub sql: String,
+    /// The snapshot is refreshed on read when it's older than this. Only by `REFRESH` when omitted.
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
@@ -0,0 +1,27 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct McpCallLogConfig {
+    #[serde(default = "default_max_records")]
+    pub max_records: usize,
+    /// JSONL file to append every call to. Nothing is written when not set. e.g. "~/.dataharpoon/mcp_calls.jsonl"
+    #[serde(default)]
+    pub audit_file: Option<String>,
+    /// Hide all argument values instead of only the ones looking like secrets.
+    #[serde(default)]
+    pub redact_arguments: bool,
+}
+
+impl Default for McpCallLogConfig {
+    fn default() -> Self {
+        Self {
+            max_records: default_max_records(),
+            audit_file: None,
+            redact_arguments: false,
+        }
+    }
+}
+
+fn default_max_records() -> usize {
+    1000
+}
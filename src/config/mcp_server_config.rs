@@ -1,3 +1,5 @@
+use crate::config::rate_limit_config::RateLimitConfig;
+use glob::Pattern;
 use serde::Deserialize;
 use std::collections::HashMap;
 
@@ -9,4 +11,32 @@ pub struct McpServerConfig {
     pub args: Vec<String>,
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// Glob patterns of tools usable from SQL and the agent. All tools are allowed when empty.
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    /// Glob patterns of tools hidden from SQL and the agent. Takes precedence over `allowed_tools`.
+    #[serde(default)]
+    pub denied_tools: Vec<String>,
+    /// Calls beyond the rate wait for their turn. Unlimited when not set.
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Calls to the server a statement can make, besides `limits.max_mcp_calls`.
+    #[serde(default)]
+    pub max_calls_per_statement: Option<usize>,
+}
+
+impl McpServerConfig {
+    pub fn is_tool_allowed(&self, tool_name: &str) -> bool {
+        let matches = |pattern: &String| {
+            Pattern::new(pattern)
+                .map(|p| p.matches(tool_name))
+                .unwrap_or(pattern == tool_name)
+        };
+
+        if !self.allowed_tools.is_empty() && !self.allowed_tools.iter().any(matches) {
+            return false;
+        }
+
+        !self.denied_tools.iter().any(matches)
+    }
 }
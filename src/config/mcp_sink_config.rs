@@ -0,0 +1,29 @@
+use crate::config::source_config::{quote_identifier, quote_literal};
+use serde::Deserialize;
+
+/// A table whose inserted rows are passed to an MCP tool, one call per row.
+#[derive(Debug, Deserialize, Clone)]
+pub struct McpSinkConfig {
+    pub name: String,
+    pub server: String,
+    pub tool: String,
+    /// Column definitions. e.g. "channel VARCHAR, text VARCHAR". Taken from the input schema of the
+    /// tool when omitted.
+    #[serde(default)]
+    pub schema: Option<String>,
+}
+
+impl McpSinkConfig {
+    pub fn create_table_sql(&self) -> String {
+        let mut sql = format!("CREATE EXTERNAL TABLE {}", quote_identifier(&self.name));
+        if let Some(schema) = &self.schema {
+            sql.push_str(&format!(" ({})", schema));
+        }
+        sql.push_str(&format!(
+            " STORED AS MCP LOCATION {}",
+            quote_literal(&format!("{}/{}", self.server, self.tool))
+        ));
+
+        sql
+    }
+}
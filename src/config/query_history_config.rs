@@ -0,0 +1,51 @@
+use crate::util::path::expand_home;
+use serde::Deserialize;
+use std::env;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct QueryHistoryConfig {
+    /// Whether to store the history to `file` so that it survives restarts. Off by default, since
+    /// the SQL is stored as written, including secrets passed as tool arguments.
+    #[serde(default)]
+    pub persist: bool,
+    /// JSONL file to store the history. Defaults to `~/.dataharpoon/query_history.jsonl`.
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Latest records to keep. `file` is cut down to them on start and when it grows to twice as
+    /// many lines.
+    #[serde(default = "default_max_records")]
+    pub max_records: usize,
+}
+
+impl Default for QueryHistoryConfig {
+    fn default() -> Self {
+        Self {
+            persist: false,
+            file: None,
+            max_records: default_max_records(),
+        }
+    }
+}
+
+impl QueryHistoryConfig {
+    pub fn history_file(&self) -> Option<PathBuf> {
+        if !self.persist {
+            return None;
+        }
+
+        if let Some(file) = &self.file {
+            return Some(expand_home(file));
+        }
+
+        Some(
+            PathBuf::from(env::var("HOME").ok()?)
+                .join(".dataharpoon")
+                .join("query_history.jsonl"),
+        )
+    }
+}
+
+fn default_max_records() -> usize {
+    10000
+}
@@ -0,0 +1,29 @@
+use serde::Deserialize;
+
+/// Token bucket for the calls to a server. The lower rate applies when both are set.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RateLimitConfig {
+    #[serde(default)]
+    pub calls_per_second: Option<f64>,
+    #[serde(default)]
+    pub calls_per_minute: Option<f64>,
+    /// Calls allowed at once after being idle. Defaults to 1.
+    #[serde(default)]
+    pub burst: Option<u32>,
+}
+
+impl RateLimitConfig {
+    /// Calls per second, or None when unlimited.
+    pub fn rate(&self) -> Option<f64> {
+        let per_minute = self.calls_per_minute.map(|calls| calls / 60.0);
+        [self.calls_per_second, per_minute]
+            .into_iter()
+            .flatten()
+            .filter(|rate| *rate > 0.0)
+            .reduce(f64::min)
+    }
+
+    pub fn burst(&self) -> f64 {
+        self.burst.unwrap_or(1).max(1) as f64
+    }
+}
@@ -0,0 +1,84 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A file-backed dataset registered as an external table.
+#[derive(Debug, Deserialize, Clone)]
+pub struct SourceConfig {
+    pub name: String,
+    /// File, directory or glob. e.g. "data/events/*.csv"
+    pub path: String,
+    /// "csv", "json", "parquet", "avro", "arrow" or "xlsx"
+    pub format: String,
+    /// Reader options passed to `OPTIONS`. e.g. { "format.delimiter" = ";" }
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+    /// Column definitions. e.g. "id BIGINT, name VARCHAR". Inferred when omitted.
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// Keys of the `key=value` directories. They must be plain identifiers. e.g. ["date"]
+    #[serde(default)]
+    pub partition_cols: Vec<String>,
+    /// Finds the partition columns from `key=value` directories such as `date=2025-06-01/`.
+    #[serde(default)]
+    pub hive_partitioning: bool,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl SourceConfig {
+    pub fn create_table_sql(&self) -> String {
+        let mut sql = format!("CREATE EXTERNAL TABLE {}", quote_identifier(&self.name));
+        if let Some(schema) = &self.schema {
+            sql.push_str(&format!(" ({})", schema));
+        }
+        sql.push_str(&format!(" STORED AS {}", self.format.to_uppercase()));
+        if !self.partition_cols.is_empty() {
+            // DataFusion takes the partition column names as written, including quotes, so they
+            // are written as they are and `validate` keeps them to plain identifiers.
+            sql.push_str(&format!(
+                " PARTITIONED BY ({})",
+                self.partition_cols.join(", ")
+            ));
+        }
+        sql.push_str(&format!(" LOCATION {}", quote_literal(&self.path)));
+        if !self.options.is_empty() {
+            let mut options = self
+                .options
+                .iter()
+                .map(|(k, v)| format!("{} {}", quote_literal(k), quote_literal(v)))
+                .collect::<Vec<_>>();
+            options.sort();
+            sql.push_str(&format!(" OPTIONS ({})", options.join(", ")));
+        }
+
+        sql
+    }
+
+    /// Rejects partition columns that are not plain identifiers, since they are written into the
+    /// SQL unquoted.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.partition_cols.iter().find(|c| !is_plain_identifier(c)) {
+            Some(col) => Err(format!(
+                "invalid partition column {:?} of source({}). It must match [A-Za-z_][A-Za-z0-9_]*",
+                col, self.name
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+pub(super) fn quote_identifier(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+pub(super) fn quote_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn is_plain_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+/// A SQLite database attached as a catalog, same as `ATTACH 'app.db' AS app`.
+#[derive(Debug, Deserialize, Clone)]
+pub struct SqliteConfig {
+    /// Catalog name. The tables are queried as `<name>.main.<table>`.
+    pub name: String,
+    pub path: String,
+}
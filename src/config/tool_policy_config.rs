@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPolicyAction {
+    Allow,
+    Confirm,
+    Deny,
+}
+
+/// Actions taken when a tool not annotated as read-only (`readOnlyHint`) is called.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ToolPolicyConfig {
+    /// For queries from the REPL, including the ones run by `/ask`.
+    #[serde(default = "default_repl_action")]
+    pub repl: ToolPolicyAction,
+    /// For queries arriving through `serve mcp`.
+    #[serde(default = "default_mcp_server_action")]
+    pub mcp_server: ToolPolicyAction,
+}
+
+impl Default for ToolPolicyConfig {
+    fn default() -> Self {
+        Self {
+            repl: default_repl_action(),
+            mcp_server: default_mcp_server_action(),
+        }
+    }
+}
+
+fn default_repl_action() -> ToolPolicyAction {
+    ToolPolicyAction::Confirm
+}
+
+fn default_mcp_server_action() -> ToolPolicyAction {
+    ToolPolicyAction::Deny
+}
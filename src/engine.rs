@@ -1,4 +1,30 @@
+mod access_policy;
+mod bind_query_state_rule;
 pub mod context;
+mod dataharpoon_options;
+mod exec_mcp_execution_plan;
+mod exec_mcp_rule;
+mod file_listing;
+mod hive_partitions;
 mod information_schema;
+mod json_shape;
+mod json_table;
+mod materialized_view;
+mod mcp_call_estimate;
+mod mcp_call_log;
+mod mcp_call_table;
+mod mcp_runtime;
+mod mcp_sink_table;
+mod mcp_sink_table_factory;
 mod mcp_tool_caller;
+mod persistent_catalog;
+mod query_history;
+mod query_state;
+mod rate_limiter;
+mod sandboxed_url_table_factory;
+mod source_table;
+mod sqlite;
+pub mod tool_policy;
 mod udf;
+mod xlsx_table;
+mod xlsx_table_factory;
@@ -0,0 +1,273 @@
+use crate::config::access_config::AccessConfig;
+use crate::engine::mcp_sink_table_factory::MCP_FILE_TYPE;
+use crate::util::path::expand_home;
+use datafusion::common::tree_node::TreeNodeRecursion;
+use datafusion::common::{Result, plan_err};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::execution::context::SQLOptions;
+use datafusion::logical_expr::dml::InsertOp;
+use datafusion::logical_expr::{DdlStatement, LogicalPlan, WriteOp};
+use log::warn;
+use std::path::{Component, Path, PathBuf};
+use url::Url;
+
+const FILE_SCHEME: &str = "file";
+
+/// Restricts the files and URLs which SQL can read from or write to.
+#[derive(Debug)]
+pub struct AccessPolicy {
+    allowed_roots: Option<Vec<PathBuf>>,
+    allowed_schemes: Option<Vec<String>>,
+    read_only: bool,
+    writable_roots: Vec<PathBuf>,
+}
+
+impl AccessPolicy {
+    pub fn new(config: &AccessConfig) -> Self {
+        let allowed_roots = config
+            .allowed_roots
+            .as_ref()
+            .map(|roots| roots.iter().map(|root| Self::resolve_root(root)).collect());
+        let allowed_schemes = config
+            .allowed_schemes
+            .as_ref()
+            .map(|schemes| schemes.iter().map(|s| s.to_lowercase()).collect());
+        let writable_roots = config
+            .writable_roots
+            .iter()
+            .map(|root| Self::resolve_root(root))
+            .collect();
+
+        Self {
+            allowed_roots,
+            allowed_schemes,
+            read_only: config.read_only.unwrap_or(false),
+            writable_roots,
+        }
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        self.allowed_roots.is_some() || self.allowed_schemes.is_some()
+    }
+
+    /// Checks a location given as a local path (globs allowed) or a URL.
+    pub fn check_url(&self, location: &str) -> Result<()> {
+        if !self.is_restricted() {
+            return Ok(());
+        }
+
+        let table_url = ListingTableUrl::parse(location)?;
+        let scheme = table_url.scheme();
+        if let Some(schemes) = &self.allowed_schemes
+            && !schemes.iter().any(|s| s == scheme)
+        {
+            return plan_err!(
+                "access to {} is denied. Scheme '{}' is not allowed. Allowed schemes: [{}]",
+                location,
+                scheme,
+                schemes.join(", ")
+            );
+        }
+
+        if scheme != FILE_SCHEME {
+            return Ok(());
+        }
+        let Some(path) = Self::local_path(&table_url) else {
+            return plan_err!(
+                "access to {} is denied. Failed to resolve the path",
+                location
+            );
+        };
+
+        self.check_local_path(location, &path)
+    }
+
+    /// Checks the locations used by `CREATE EXTERNAL TABLE` and `COPY` in the plan.
+    pub fn check_plan(&self, plan: &LogicalPlan) -> Result<()> {
+        if !self.is_restricted() {
+            return Ok(());
+        }
+
+        plan.apply_with_subqueries(|node| {
+            match node {
+                // The location of mcp sinks is `server/tool`, not a file.
+                LogicalPlan::Ddl(DdlStatement::CreateExternalTable(cmd))
+                    if cmd.file_type != MCP_FILE_TYPE =>
+                {
+                    self.check_url(&cmd.location)?
+                }
+                LogicalPlan::Copy(copy) => self.check_url(&copy.output_url)?,
+                _ => {}
+            }
+            Ok(TreeNodeRecursion::Continue)
+        })?;
+
+        Ok(())
+    }
+
+    /// Rejects statements other than queries in read-only mode, except `COPY` to the writable roots.
+    pub fn check_read_only(&self, plan: &LogicalPlan) -> Result<()> {
+        if !self.read_only {
+            return Ok(());
+        }
+
+        if let LogicalPlan::Copy(copy) = plan
+            && self.is_writable(&copy.output_url)
+        {
+            return Ok(());
+        }
+
+        let options = SQLOptions::new()
+            .with_allow_ddl(false)
+            .with_allow_dml(false);
+        if options.verify_plan(plan).is_ok() {
+            return Ok(());
+        }
+
+        let statement_type = Self::write_statement_type(plan).unwrap_or("the statement");
+        self.read_only_error(statement_type)
+    }
+
+    /// Rejects the statement, which isn't planned by DataFusion such as `ATTACH`, in read-only mode.
+    pub fn check_read_only_statement(&self, statement_type: &str) -> Result<()> {
+        if !self.read_only {
+            return Ok(());
+        }
+
+        self.read_only_error(statement_type)
+    }
+
+    fn read_only_error(&self, statement_type: &str) -> Result<()> {
+        if self.writable_roots.is_empty() {
+            plan_err!(
+                "{} is not allowed in read-only mode. Only queries can be run",
+                statement_type
+            )
+        } else {
+            plan_err!(
+                "{} is not allowed in read-only mode. Only queries and COPY to the writable roots can be run: [{}]",
+                statement_type,
+                Self::display_roots(&self.writable_roots)
+            )
+        }
+    }
+
+    fn is_writable(&self, location: &str) -> bool {
+        let Ok(table_url) = ListingTableUrl::parse(location) else {
+            return false;
+        };
+        let Some(path) = Self::local_path(&table_url).and_then(|p| Self::resolve_path(&p)) else {
+            return false;
+        };
+
+        self.writable_roots
+            .iter()
+            .any(|root| path.starts_with(root))
+    }
+
+    fn write_statement_type(plan: &LogicalPlan) -> Option<&'static str> {
+        let mut statement_type = None;
+        let _ = plan.apply_with_subqueries(|node| {
+            statement_type = match node {
+                LogicalPlan::Ddl(ddl) => Some(match ddl {
+                    DdlStatement::CreateExternalTable(_) => "CREATE EXTERNAL TABLE",
+                    DdlStatement::CreateMemoryTable(_) => "CREATE TABLE",
+                    DdlStatement::CreateView(_) => "CREATE VIEW",
+                    DdlStatement::CreateCatalogSchema(_) => "CREATE SCHEMA",
+                    DdlStatement::CreateCatalog(_) => "CREATE DATABASE",
+                    DdlStatement::CreateIndex(_) => "CREATE INDEX",
+                    DdlStatement::DropTable(_) => "DROP TABLE",
+                    DdlStatement::DropView(_) => "DROP VIEW",
+                    DdlStatement::DropCatalogSchema(_) => "DROP SCHEMA",
+                    DdlStatement::CreateFunction(_) => "CREATE FUNCTION",
+                    DdlStatement::DropFunction(_) => "DROP FUNCTION",
+                }),
+                LogicalPlan::Dml(dml) => Some(match dml.op {
+                    WriteOp::Insert(InsertOp::Append) => "INSERT INTO",
+                    WriteOp::Insert(InsertOp::Overwrite) => "INSERT OVERWRITE",
+                    WriteOp::Insert(InsertOp::Replace) => "REPLACE INTO",
+                    WriteOp::Delete => "DELETE",
+                    WriteOp::Update => "UPDATE",
+                    WriteOp::Ctas => "CREATE TABLE AS",
+                }),
+                LogicalPlan::Copy(_) => Some("COPY"),
+                _ => None,
+            };
+            Ok(match statement_type {
+                Some(_) => TreeNodeRecursion::Stop,
+                None => TreeNodeRecursion::Continue,
+            })
+        });
+
+        statement_type
+    }
+
+    /// Returns the local path of the url, without the glob part.
+    pub fn local_path(table_url: &ListingTableUrl) -> Option<PathBuf> {
+        if table_url.scheme() != FILE_SCHEME {
+            return None;
+        }
+        let url: &Url = table_url.as_ref();
+        url.to_file_path().ok()
+    }
+
+    fn check_local_path(&self, location: &str, path: &Path) -> Result<()> {
+        let Some(roots) = &self.allowed_roots else {
+            return Ok(());
+        };
+
+        if let Some(resolved) = Self::resolve_path(path)
+            && roots.iter().any(|root| resolved.starts_with(root))
+        {
+            return Ok(());
+        }
+
+        plan_err!(
+            "access to {} is denied. It is outside of the allowed roots: [{}]",
+            location,
+            Self::display_roots(roots)
+        )
+    }
+
+    fn display_roots(roots: &[PathBuf]) -> String {
+        roots
+            .iter()
+            .map(|r| r.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Resolves symlinks and `..` of the longest existing ancestor so that the path can't escape
+    /// the roots. Returns None if `..` remains in the part that doesn't exist yet.
+    fn resolve_path(path: &Path) -> Option<PathBuf> {
+        let mut existing = path.to_path_buf();
+        let mut rest = Vec::new();
+        while !existing.exists() {
+            rest.push(existing.file_name()?.to_os_string());
+            existing = existing.parent()?.to_path_buf();
+        }
+
+        let mut resolved = existing.canonicalize().ok()?;
+        for name in rest.iter().rev() {
+            let name = Path::new(name);
+            if name.components().any(|c| c == Component::ParentDir) {
+                return None;
+            }
+            resolved.push(name);
+        }
+
+        Some(resolved)
+    }
+
+    fn resolve_root(root: &str) -> PathBuf {
+        let root = expand_home(root);
+
+        match root.canonicalize() {
+            Ok(root) => root,
+            Err(e) => {
+                warn!("failed to resolve allowed root({}). {}", root.display(), e);
+                root
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::engine::query_state::{QueryState, QueryStateExtension};
+use crate::engine::udf::exec_mcp_batch_function::ExecMcpBatchFunction;
+use crate::engine::udf::exec_mcp_function::ExecMcpFunction;
+use crate::engine::udf::mcp_agg_function::McpAggFunction;
+use datafusion::common::Result;
+use datafusion::common::config::ConfigOptions;
+use datafusion::common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion::logical_expr::expr::WindowFunction;
+use datafusion::logical_expr::{
+    AggregateUDF, Expr, LogicalPlan, ScalarUDF, WindowFunctionDefinition,
+};
+use datafusion::optimizer::AnalyzerRule;
+use std::sync::Arc;
+
+/// Binds exec_mcp, exec_mcp_batch and mcp_agg to the state of the statement in the session
+/// options, so that their calls share the confirmations and the call budget of the statement.
+/// The calls in views are bound as well, since the views are inlined before this rule.
+#[derive(Debug, Default)]
+pub struct BindQueryStateRule {}
+
+impl AnalyzerRule for BindQueryStateRule {
+    fn analyze(&self, plan: LogicalPlan, config: &ConfigOptions) -> Result<LogicalPlan> {
+        let query_state = QueryStateExtension::query_state(config);
+
+        plan.transform_up_with_subqueries(|plan| {
+            plan.map_expressions(|expr| expr.transform_up(|expr| Self::bind(expr, &query_state)))
+        })
+        .data()
+    }
+
+    fn name(&self) -> &str {
+        "bind_query_state"
+    }
+}
+
+impl BindQueryStateRule {
+    /// Binds the calls in an expression left out of the plan, such as an argument of a table function.
+    pub fn bind_expr(expr: Expr, query_state: &Arc<QueryState>) -> Result<Expr> {
+        expr.transform_up(|expr| Self::bind(expr, query_state))
+            .data()
+    }
+
+    fn bind(expr: Expr, query_state: &Arc<QueryState>) -> Result<Transformed<Expr>> {
+        match expr {
+            Expr::ScalarFunction(mut f) => match Self::bind_scalar(&f.func, query_state) {
+                Some(func) => {
+                    f.func = Arc::new(func);
+                    Ok(Transformed::yes(Expr::ScalarFunction(f)))
+                }
+                None => Ok(Transformed::no(Expr::ScalarFunction(f))),
+            },
+            Expr::AggregateFunction(mut f) => match Self::bind_aggregate(&f.func, query_state) {
+                Some(func) => {
+                    f.func = Arc::new(func);
+                    Ok(Transformed::yes(Expr::AggregateFunction(f)))
+                }
+                None => Ok(Transformed::no(Expr::AggregateFunction(f))),
+            },
+            Expr::WindowFunction(WindowFunction {
+                fun: WindowFunctionDefinition::AggregateUDF(func),
+                params,
+            }) => match Self::bind_aggregate(&func, query_state) {
+                Some(func) => Ok(Transformed::yes(Expr::WindowFunction(WindowFunction {
+                    fun: WindowFunctionDefinition::AggregateUDF(Arc::new(func)),
+                    params,
+                }))),
+                None => Ok(Transformed::no(Expr::WindowFunction(WindowFunction {
+                    fun: WindowFunctionDefinition::AggregateUDF(func),
+                    params,
+                }))),
+            },
+            expr => Ok(Transformed::no(expr)),
+        }
+    }
+
+    fn bind_scalar(func: &ScalarUDF, query_state: &Arc<QueryState>) -> Option<ScalarUDF> {
+        let inner = func.inner().as_any();
+        if let Some(f) = inner.downcast_ref::<ExecMcpFunction>() {
+            return Some(ScalarUDF::from(f.with_query_state(query_state.clone())));
+        }
+        if let Some(f) = inner.downcast_ref::<ExecMcpBatchFunction>() {
+            return Some(ScalarUDF::from(f.with_query_state(query_state.clone())));
+        }
+
+        None
+    }
+
+    fn bind_aggregate(func: &AggregateUDF, query_state: &Arc<QueryState>) -> Option<AggregateUDF> {
+        func.inner()
+            .as_any()
+            .downcast_ref::<McpAggFunction>()
+            .map(|f| AggregateUDF::from(f.with_query_state(query_state.clone())))
+    }
+}
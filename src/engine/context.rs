@@ -1,55 +1,283 @@
 use crate::config::config::Config;
+use crate::config::limits_config::LimitsConfig;
+use crate::config::materialized_view_config::MaterializedViewConfig;
 use crate::config::mcp_server_config::McpServerConfig;
+use crate::config::mcp_sink_config::McpSinkConfig;
+use crate::config::source_config::SourceConfig;
+use crate::config::sqlite_config::SqliteConfig;
+use crate::engine::access_policy::AccessPolicy;
+use crate::engine::bind_query_state_rule::BindQueryStateRule;
+use crate::engine::dataharpoon_options::DataHarpoonOptions;
+use crate::engine::exec_mcp_rule::ExecMcpRule;
 use crate::engine::information_schema::information_schema_provider::{
     INFORMATION_SCHEMA_NAME, InformationSchemaProvider,
 };
+use crate::engine::materialized_view::materialized_view_statement::{
+    try_materialized_view_statement, try_refresh_statement,
+};
+use crate::engine::materialized_view::materialized_view_store::MaterializedViewStore;
+use crate::engine::mcp_call_estimate::estimate_mcp_calls;
+use crate::engine::mcp_runtime::McpRuntime;
+use crate::engine::mcp_sink_table_factory::{MCP_FILE_TYPE, McpSinkTableFactory};
+use crate::engine::persistent_catalog::PersistentCatalog;
+use crate::engine::query_history::{QueryHistory, QueryHistoryRecord, QueryStatus};
+use crate::engine::query_state::{QueryState, QueryStateExtension};
+use crate::engine::sandboxed_url_table_factory::SandboxedUrlTableFactory;
+use crate::engine::source_table::{create_external_table, create_source_table};
+use crate::engine::sqlite::sqlite_attach::{attach_sqlite, try_attach_statement};
+use crate::engine::tool_policy::ToolCallConfirmer;
 use crate::engine::udf::call_mcp_function::{CALL_MCP_FUNCTION_NAME, CallMcpFunction};
+use crate::engine::udf::exec_mcp_batch_function::ExecMcpBatchFunction;
 use crate::engine::udf::exec_mcp_function::ExecMcpFunction;
+use crate::engine::udf::json_each_function::{JSON_EACH_FUNCTION_NAME, JsonEachFunction};
+use crate::engine::udf::json_get_function::{JsonGetFunction, JsonGetType};
+use crate::engine::udf::json_query_function::{JsonQuery, JsonQueryFunction};
+use crate::engine::udf::mcp_agg_function::McpAggFunction;
+use crate::engine::udf::named_arguments::rewrite_named_arguments;
+use crate::engine::udf::read_context::ReadContext;
+use crate::engine::udf::read_file_function::{ReadFileFunction, ReadFormat};
+use crate::engine::udf::read_text_function::{READ_TEXT_FUNCTION_NAME, ReadTextFunction};
+use crate::engine::udf::read_xlsx_function::{READ_XLSX_FUNCTION_NAME, ReadXlsxFunction};
+use crate::engine::udf::xlsx_sheets_function::{XLSX_SHEETS_FUNCTION_NAME, XlsxSheetsFunction};
+use crate::engine::xlsx_table_factory::{XLSX_FILE_TYPE, XlsxTableFactory};
+use crate::model::engine::query_result::QueryResult;
+use crate::model::engine::query_source::QuerySource;
+use chrono::Utc;
 use dashmap::DashMap;
+use datafusion::arrow::array::RecordBatch;
+use datafusion::catalog::DynamicFileCatalog;
+use datafusion::common::{TableReference, exec_err};
 use datafusion::dataframe::DataFrame;
 use datafusion::error::Result;
-use datafusion::logical_expr::ScalarUDF;
-use datafusion::prelude::SessionContext;
+use datafusion::execution::SessionState;
+use datafusion::execution::disk_manager::DiskManagerConfig;
+use datafusion::execution::runtime_env::{RuntimeEnv, RuntimeEnvBuilder};
+use datafusion::logical_expr::{AggregateUDF, LogicalPlan, ScalarUDF, Statement};
+use datafusion::physical_plan::{ExecutionPlan, execute_stream};
+use datafusion::prelude::{SessionConfig, SessionContext};
+use datafusion::sql::parser::Statement as DFStatement;
+use futures::StreamExt;
+use log::warn;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 pub struct Context {
     context: SessionContext,
     context_config: ContextConfig,
+    call_mcp: Arc<CallMcpFunction>,
+    query_count: AtomicU64,
 }
 
 struct ContextConfig {
     mcp_servers: DashMap<String, Arc<McpServerConfig>>,
+    mcp_runtime: Arc<McpRuntime>,
+    query_history: Arc<QueryHistory>,
+    access_policy: Arc<AccessPolicy>,
+    catalog: Arc<PersistentCatalog>,
+    materialized_views: Arc<MaterializedViewStore>,
+    limits: LimitsConfig,
+    sources: Vec<SourceConfig>,
+    sqlite: Vec<SqliteConfig>,
+    materialized_view_configs: Vec<MaterializedViewConfig>,
+    mcp_sinks: Vec<McpSinkConfig>,
 }
 
 impl ContextConfig {
     fn new(config: Config) -> ContextConfig {
         let mcp_servers = DashMap::<String, Arc<McpServerConfig>>::new();
-        for mcp in config.mcp_servers {
-            mcp_servers.insert(mcp.name.clone(), Arc::new(mcp));
+        for mcp in config.mcp_servers.iter() {
+            mcp_servers.insert(mcp.name.clone(), Arc::new(mcp.clone()));
         }
 
-        Self { mcp_servers }
+        let mcp_runtime = Arc::new(McpRuntime::new(&config));
+        let query_history = Arc::new(QueryHistory::new(&config.query_history));
+        let access_policy = Arc::new(AccessPolicy::new(&config.access));
+        let catalog = Arc::new(PersistentCatalog::new(&config.catalog));
+        let materialized_views = Arc::new(MaterializedViewStore::new(
+            &config.cache,
+            access_policy.clone(),
+        ));
+
+        Self {
+            mcp_servers,
+            mcp_runtime,
+            query_history,
+            access_policy,
+            catalog,
+            materialized_views,
+            limits: config.limits.clone(),
+            sources: config.sources.clone(),
+            sqlite: config.sqlite.clone(),
+            materialized_view_configs: config.materialized_views.clone(),
+            mcp_sinks: config.mcp_sinks.clone(),
+        }
     }
 }
 
 impl Context {
-    pub fn new(config: Config) -> Context {
-        let context = SessionContext::new().enable_url_table();
+    pub async fn new(config: Config) -> Context {
         let context_config = ContextConfig::new(config);
+        let context = Self::create_session_context(&context_config);
 
         Self::register_information_schema(&context, &context_config);
 
-        let exec_mcp_func =
-            ScalarUDF::from(ExecMcpFunction::new(context_config.mcp_servers.clone()));
-        context.register_udf(exec_mcp_func);
-
-        let call_mcp_func = CallMcpFunction::new(context_config.mcp_servers.clone());
-        context.register_udtf(CALL_MCP_FUNCTION_NAME, Arc::new(call_mcp_func));
+        let call_mcp = Self::register_mcp_functions(&context, &context_config);
+        Self::register_read_functions(&context, &context_config);
+        Self::register_json_functions(&context);
 
-        Self {
+        let ctx = Self {
             context,
             context_config,
+            call_mcp,
+            query_count: AtomicU64::new(0),
+        };
+        ctx.register_sources().await;
+        ctx.register_sqlite_databases();
+        ctx.register_mcp_sinks().await;
+        ctx.context_config
+            .materialized_views
+            .restore(&ctx.context, &ctx.context_config.materialized_view_configs)
+            .await;
+        ctx.restore_catalog().await;
+
+        ctx
+    }
+
+    /// Same as `SessionContext::enable_url_table` but the file paths and URLs are checked by the
+    /// access policy.
+    fn create_session_context(context_config: &ContextConfig) -> SessionContext {
+        let options = DataHarpoonOptions {
+            max_mcp_calls: context_config.limits.max_mcp_calls,
+        };
+        let context = SessionContext::new_with_config_rt(
+            SessionConfig::new().with_option_extension(options),
+            Self::create_runtime(&context_config.limits),
+        );
+        let factory = Arc::new(SandboxedUrlTableFactory::new(
+            context_config.access_policy.clone(),
+        ));
+        let mcp_sink_factory = Arc::new(McpSinkTableFactory::new(
+            context_config.mcp_servers.clone(),
+            context_config.mcp_runtime.clone(),
+        ));
+        let catalog_list = Arc::new(DynamicFileCatalog::new(
+            context.state().catalog_list().clone(),
+            factory.clone(),
+        ));
+
+        let session_id = context.session_id();
+        let context: SessionContext = context
+            .into_state_builder()
+            .with_session_id(session_id)
+            .with_catalog_list(catalog_list)
+            .with_table_factory(
+                XLSX_FILE_TYPE.to_string(),
+                Arc::new(XlsxTableFactory::default()),
+            )
+            .with_table_factory(MCP_FILE_TYPE.to_string(), mcp_sink_factory)
+            .with_analyzer_rule(Arc::new(BindQueryStateRule::default()))
+            .with_physical_optimizer_rule(Arc::new(ExecMcpRule::default()))
+            .build()
+            .into();
+        factory.session_store().with_state(context.state_weak_ref());
+
+        context
+    }
+
+    fn create_runtime(limits: &LimitsConfig) -> Arc<RuntimeEnv> {
+        let mut builder = RuntimeEnvBuilder::new();
+        if let Some(memory_limit_mb) = limits.memory_limit_mb {
+            builder = builder.with_memory_limit(memory_limit_mb * 1024 * 1024, 1.0);
+        }
+        if let Some(spill_dir) = &limits.spill_dir {
+            if let Err(e) = fs::create_dir_all(spill_dir) {
+                warn!("failed to create spill directory({}). {}", spill_dir, e);
+            }
+            builder =
+                builder.with_disk_manager(DiskManagerConfig::NewSpecified(vec![PathBuf::from(
+                    spill_dir,
+                )]));
+        }
+
+        builder.build_arc().unwrap()
+    }
+
+    /// Returns call_mcp, which is registered again for each statement to plan its calls.
+    fn register_mcp_functions(
+        context: &SessionContext,
+        context_config: &ContextConfig,
+    ) -> Arc<CallMcpFunction> {
+        let exec_mcp_func = ScalarUDF::from(ExecMcpFunction::new(
+            context_config.mcp_servers.clone(),
+            context_config.mcp_runtime.clone(),
+        ));
+        context.register_udf(exec_mcp_func);
+        let exec_mcp_batch_func = ScalarUDF::from(ExecMcpBatchFunction::new(
+            context_config.mcp_servers.clone(),
+            context_config.mcp_runtime.clone(),
+        ));
+        context.register_udf(exec_mcp_batch_func);
+        let mcp_agg_func = AggregateUDF::from(McpAggFunction::new(
+            context_config.mcp_servers.clone(),
+            context_config.mcp_runtime.clone(),
+            &context_config.limits,
+        ));
+        context.register_udaf(mcp_agg_func);
+
+        let read_context = ReadContext::new(context_config.access_policy.clone());
+        let call_mcp_func = Arc::new(CallMcpFunction::new(
+            context_config.mcp_servers.clone(),
+            context_config.mcp_runtime.clone(),
+            read_context.clone(),
+        ));
+        context.register_udtf(CALL_MCP_FUNCTION_NAME, call_mcp_func.clone());
+
+        read_context
+            .session_store()
+            .with_state(context.state_weak_ref());
+
+        call_mcp_func
+    }
+
+    fn register_read_functions(context: &SessionContext, context_config: &ContextConfig) {
+        let read_context = ReadContext::new(context_config.access_policy.clone());
+
+        for format in [ReadFormat::Csv, ReadFormat::Json, ReadFormat::Parquet] {
+            let read_func = ReadFileFunction::new(format, read_context.clone());
+            context.register_udtf(format.function_name(), Arc::new(read_func));
         }
+        let read_text_func = ReadTextFunction::new(read_context.clone());
+        context.register_udtf(READ_TEXT_FUNCTION_NAME, Arc::new(read_text_func));
+        let read_xlsx_func = ReadXlsxFunction::new(read_context.clone());
+        context.register_udtf(READ_XLSX_FUNCTION_NAME, Arc::new(read_xlsx_func));
+        let xlsx_sheets_func = XlsxSheetsFunction::new(read_context.clone());
+        context.register_udtf(XLSX_SHEETS_FUNCTION_NAME, Arc::new(xlsx_sheets_func));
+
+        read_context
+            .session_store()
+            .with_state(context.state_weak_ref());
+    }
+
+    fn register_json_functions(context: &SessionContext) {
+        for get_type in [
+            JsonGetType::Str,
+            JsonGetType::Int,
+            JsonGetType::Float,
+            JsonGetType::Bool,
+        ] {
+            context.register_udf(ScalarUDF::from(JsonGetFunction::new(get_type)));
+        }
+        for query in [JsonQuery::Extract, JsonQuery::ArrayLength, JsonQuery::Keys] {
+            context.register_udf(ScalarUDF::from(JsonQueryFunction::new(query)));
+        }
+        context.register_udtf(
+            JSON_EACH_FUNCTION_NAME,
+            Arc::new(JsonEachFunction::default()),
+        );
     }
 
     fn register_information_schema(context: &SessionContext, context_config: &ContextConfig) {
@@ -60,8 +288,13 @@ impl Context {
             .default_catalog
             .clone();
 
-        let information_schema_provider =
-            InformationSchemaProvider::new(context_config.mcp_servers.clone());
+        let information_schema_provider = InformationSchemaProvider::new(
+            context_config.mcp_servers.clone(),
+            context_config.mcp_runtime.call_log.clone(),
+            context_config.query_history.clone(),
+            &context_config.sources,
+            context_config.materialized_views.clone(),
+        );
         context
             .catalog(information_schema_catalog.as_str())
             .unwrap()
@@ -72,7 +305,405 @@ impl Context {
             .unwrap();
     }
 
-    pub async fn run_sql(&self, sql: &str) -> Result<DataFrame> {
-        self.context.sql(sql).await
+    /// Registers `[[sources]]` as external tables. They are set by the config owner, so they are
+    /// created even in read-only mode.
+    async fn register_sources(&self) {
+        let state = self.context.state();
+        for source in self.context_config.sources.iter() {
+            let result = create_source_table(&state, source).await.and_then(|table| {
+                self.context
+                    .register_table(TableReference::bare(source.name.clone()), table)
+            });
+            if let Err(e) = result {
+                warn!("failed to register source({}). {}", source.name, e);
+            }
+        }
+    }
+
+    /// Registers `[[mcp_sinks]]`. Inserting into them is still rejected in read-only mode.
+    async fn register_mcp_sinks(&self) {
+        let state = self.context.state();
+        for sink in self.context_config.mcp_sinks.iter() {
+            let result = create_external_table(&state, &sink.name, &sink.create_table_sql())
+                .await
+                .and_then(|table| {
+                    self.context
+                        .register_table(TableReference::bare(sink.name.clone()), table)
+                });
+            if let Err(e) = result {
+                warn!("failed to register mcp sink({}). {}", sink.name, e);
+            }
+        }
+    }
+
+    /// Attaches `[[sqlite]]` as catalogs.
+    fn register_sqlite_databases(&self) {
+        for sqlite in self.context_config.sqlite.iter() {
+            if let Err(e) = attach_sqlite(&self.context, &sqlite.name, &sqlite.path) {
+                warn!("failed to attach sqlite({}). {}", sqlite.name, e);
+            }
+        }
+    }
+
+    /// Replays the stored `CREATE VIEW` and `CREATE EXTERNAL TABLE`. They were accepted when stored,
+    /// so they are created even in read-only mode, but the locations are checked again.
+    async fn restore_catalog(&self) {
+        let access_policy = &self.context_config.access_policy;
+        for entry in self.context_config.catalog.entries() {
+            let result = async {
+                let plan = Self::create_logical_plan(&self.context.state(), &entry.sql).await?;
+                access_policy.check_plan(&plan)?;
+                self.context.execute_logical_plan(plan).await
+            }
+            .await;
+            if let Err(e) = result {
+                warn!("failed to restore {}. {}", entry.name, e);
+            }
+        }
+    }
+
+    /// Sets the confirmer asked before calling tools not annotated as read-only.
+    pub fn with_tool_call_confirmer(self, confirmer: Arc<dyn ToolCallConfirmer>) -> Self {
+        self.context_config
+            .mcp_runtime
+            .tool_policy
+            .set_confirmer(confirmer);
+        self
+    }
+
+    /// Runs the sql internally. The result isn't truncated nor recorded to the query history.
+    pub async fn run_sql(&self, sql: &str) -> Result<Vec<RecordBatch>> {
+        let query_state = self.start_query(self.next_query_id(), None);
+
+        let context = self.context.clone();
+        let call_mcp = self.call_mcp.clone();
+        let access_policy = self.context_config.access_policy.clone();
+        let catalog = self.context_config.catalog.clone();
+        let materialized_views = self.context_config.materialized_views.clone();
+        let sql = sql.to_string();
+        let statement_state = query_state.clone();
+        let result = self
+            .with_timeout(async move {
+                Self::create_dataframe(
+                    &context,
+                    &call_mcp,
+                    &statement_state,
+                    &access_policy,
+                    &catalog,
+                    &materialized_views,
+                    &sql,
+                )
+                .await?
+                .collect()
+                .await
+            })
+            .await;
+        query_state.finish();
+
+        result
+    }
+
+    /// Runs the sql and collects the result, recording it to the query history.
+    pub async fn execute_sql(&self, sql: &str, source: QuerySource) -> Result<QueryResult> {
+        self.collect_sql(sql, source, None).await
+    }
+
+    /// Same as `execute_sql` but collects only the first `rows` rows.
+    pub async fn preview_sql(
+        &self,
+        sql: &str,
+        source: QuerySource,
+        rows: usize,
+    ) -> Result<Vec<RecordBatch>> {
+        let result = self.collect_sql(sql, source, Some(rows)).await?;
+        Ok(result.batches)
+    }
+
+    async fn collect_sql(
+        &self,
+        sql: &str,
+        source: QuerySource,
+        limit: Option<usize>,
+    ) -> Result<QueryResult> {
+        let query_id = self.next_query_id();
+        let query_state = self.start_query(query_id.clone(), Some(source));
+        let started_at = Utc::now();
+        let timer = Instant::now();
+
+        let context = self.context.clone();
+        let call_mcp = self.call_mcp.clone();
+        let access_policy = self.context_config.access_policy.clone();
+        let catalog = self.context_config.catalog.clone();
+        let materialized_views = self.context_config.materialized_views.clone();
+        let limits = self.context_config.limits.clone();
+        let owned_sql = sql.to_string();
+        let statement_state = query_state.clone();
+        let result = self
+            .with_timeout(async move {
+                let mut df = Self::create_dataframe(
+                    &context,
+                    &call_mcp,
+                    &statement_state,
+                    &access_policy,
+                    &catalog,
+                    &materialized_views,
+                    &owned_sql,
+                )
+                .await?;
+                if limit.is_some() {
+                    df = df.limit(0, limit)?;
+                }
+                Self::collect_limited(df, &limits, &statement_state).await
+            })
+            .await;
+        query_state.finish();
+
+        let (row_count, status, error_message) = match &result {
+            Ok(result) => (
+                Some(result.batches.iter().map(|b| b.num_rows() as i64).sum()),
+                QueryStatus::Success,
+                None,
+            ),
+            Err(e) => (None, QueryStatus::Error, Some(e.to_string())),
+        };
+        self.context_config
+            .query_history
+            .record(QueryHistoryRecord {
+                query_id,
+                source,
+                sql: sql.to_string(),
+                started_at,
+                duration_ms: timer.elapsed().as_millis() as i64,
+                row_count,
+                status,
+                error_message,
+            });
+
+        result
+    }
+
+    /// Runs the statement on another task so that the timeout fires even while it keeps the CPU.
+    async fn with_timeout<T: Send + 'static>(
+        &self,
+        f: impl Future<Output = Result<T>> + Send + 'static,
+    ) -> Result<T> {
+        let Some(timeout_secs) = self.context_config.limits.statement_timeout_secs else {
+            return f.await;
+        };
+
+        let handle = tokio::spawn(f);
+        let abort_handle = handle.abort_handle();
+        match tokio::time::timeout(Duration::from_secs(timeout_secs), handle).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => exec_err!("failed to run the statement. {}", e),
+            Err(_) => {
+                abort_handle.abort();
+                exec_err!(
+                    "statement timed out after {} seconds. (limits.statement_timeout_secs)",
+                    timeout_secs
+                )
+            }
+        }
+    }
+
+    /// Collects the result up to `max_result_rows` and `max_result_bytes`.
+    async fn collect_limited(
+        df: DataFrame,
+        limits: &LimitsConfig,
+        query_state: &QueryState,
+    ) -> Result<QueryResult> {
+        let task_context = Arc::new(df.task_ctx());
+        let plan = df.create_physical_plan().await?;
+        let mut notices = Self::check_mcp_call_estimate(&plan, query_state);
+        let mut stream = execute_stream(plan, task_context)?;
+
+        let mut batches = Vec::new();
+        let mut notice = None;
+        let mut rows = 0;
+        let mut bytes = 0;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            let size = Self::batch_bytes(&batch);
+
+            if let Some(max_rows) = limits.max_result_rows
+                && rows + batch.num_rows() > max_rows
+            {
+                batches.push(batch.slice(0, max_rows - rows));
+                notice = Some(format!(
+                    "The result is truncated to {} rows. (limits.max_result_rows)",
+                    max_rows
+                ));
+                break;
+            }
+            if let Some(max_bytes) = limits.max_result_bytes
+                && bytes + size > max_bytes
+            {
+                let fit_rows = (max_bytes - bytes) * batch.num_rows() / size.max(1);
+                batches.push(batch.slice(0, fit_rows));
+                notice = Some(format!(
+                    "The result is truncated to {} rows to fit in {} bytes. (limits.max_result_bytes)",
+                    rows + fit_rows,
+                    max_bytes
+                ));
+                break;
+            }
+
+            rows += batch.num_rows();
+            bytes += size;
+            batches.push(batch);
+        }
+
+        notices.extend(notice);
+        let notice = (!notices.is_empty()).then(|| notices.join("\n"));
+        Ok(QueryResult { batches, notice })
+    }
+
+    /// Bytes of the rows of the batch. Unlike `get_array_memory_size`, a slice counts only its own
+    /// rows rather than the whole buffers it shares.
+    fn batch_bytes(batch: &RecordBatch) -> usize {
+        batch
+            .columns()
+            .iter()
+            .map(|array| {
+                array
+                    .to_data()
+                    .get_slice_memory_size()
+                    .unwrap_or_else(|_| array.get_array_memory_size())
+            })
+            .sum()
+    }
+
+    /// Warns before the execution when the plan looks like making more MCP calls than allowed.
+    fn check_mcp_call_estimate(
+        plan: &Arc<dyn ExecutionPlan>,
+        query_state: &QueryState,
+    ) -> Vec<String> {
+        let Some(max_calls) = query_state.max_mcp_calls() else {
+            return vec![];
+        };
+        let calls = estimate_mcp_calls(plan);
+        if calls <= max_calls {
+            return vec![];
+        }
+
+        let message = format!(
+            "The statement is estimated to make about {} MCP calls, over the limit of {}. It fails when it reaches the limit. (limits.max_mcp_calls or SET dataharpoon.max_mcp_calls)",
+            calls, max_calls
+        );
+        warn!("{}", message);
+        vec![message]
+    }
+
+    /// Plans and runs the statement on a context of the statement. Only the statements changing
+    /// the session, such as `SET`, run on `context` so that they last after the statement.
+    async fn create_dataframe(
+        context: &SessionContext,
+        call_mcp: &CallMcpFunction,
+        query_state: &Arc<QueryState>,
+        access_policy: &AccessPolicy,
+        catalog: &PersistentCatalog,
+        materialized_views: &MaterializedViewStore,
+        sql: &str,
+    ) -> Result<DataFrame> {
+        let statement_context = &Self::statement_context(context, call_mcp, query_state);
+        if let Some(df) = try_refresh_statement(statement_context, materialized_views, sql).await? {
+            return Ok(df);
+        }
+
+        let mut state = statement_context.state();
+        let dialect = state.config().options().sql_parser.dialect.clone();
+        let mut statement = state.sql_to_statement(sql, &dialect)?;
+        if let DFStatement::Explain(explain) = &statement
+            && !explain.analyze
+        {
+            // EXPLAIN only plans the statement, so it doesn't call tools to find the columns.
+            state.register_udtf(
+                CALL_MCP_FUNCTION_NAME,
+                Arc::new(call_mcp.with_query_state(query_state.clone(), false)),
+            );
+        }
+        if let Some(df) = try_attach_statement(statement_context, access_policy, &statement)? {
+            return Ok(df);
+        }
+        if let Some(df) = try_materialized_view_statement(
+            statement_context,
+            access_policy,
+            materialized_views,
+            &statement,
+        )
+        .await?
+        {
+            return Ok(df);
+        }
+        rewrite_named_arguments(&mut statement);
+
+        let plan = state.statement_to_plan(statement).await?;
+        access_policy.check_read_only(&plan)?;
+        access_policy.check_plan(&plan)?;
+
+        let change = catalog.change_of(&plan, sql, &state);
+        let df = match plan {
+            LogicalPlan::Statement(
+                Statement::SetVariable(_) | Statement::Prepare(_) | Statement::Deallocate(_),
+            ) => context.execute_logical_plan(plan).await?,
+            plan => statement_context.execute_logical_plan(plan).await?,
+        };
+        if let Some(change) = change {
+            catalog.apply(change);
+        }
+
+        Ok(df)
+    }
+
+    /// Plans the sql without the checks of the access policy.
+    pub(super) async fn create_logical_plan(
+        state: &SessionState,
+        sql: &str,
+    ) -> Result<LogicalPlan> {
+        let dialect = state.config().options().sql_parser.dialect.clone();
+        let mut statement = state.sql_to_statement(sql, &dialect)?;
+        rewrite_named_arguments(&mut statement);
+
+        state.statement_to_plan(statement).await
+    }
+
+    fn start_query(&self, query_id: String, source: Option<QuerySource>) -> Arc<QueryState> {
+        Arc::new(QueryState::new(query_id, source, self.max_mcp_calls()))
+    }
+
+    /// Context of a statement. It shares the catalogs and the settings of `context`, and carries
+    /// the state of the statement to the functions and the plans.
+    fn statement_context(
+        context: &SessionContext,
+        call_mcp: &CallMcpFunction,
+        query_state: &Arc<QueryState>,
+    ) -> SessionContext {
+        let mut state = context.state();
+        state
+            .config_mut()
+            .options_mut()
+            .extensions
+            .insert(QueryStateExtension(query_state.clone()));
+        state.register_udtf(
+            CALL_MCP_FUNCTION_NAME,
+            Arc::new(call_mcp.with_query_state(query_state.clone(), true)),
+        );
+
+        SessionContext::new_with_state(state)
+    }
+
+    /// `dataharpoon.max_mcp_calls` of the session, which starts as `limits.max_mcp_calls`.
+    fn max_mcp_calls(&self) -> Option<usize> {
+        self.context
+            .copied_config()
+            .options()
+            .extensions
+            .get::<DataHarpoonOptions>()
+            .and_then(|options| options.max_mcp_calls)
+    }
+
+    fn next_query_id(&self) -> String {
+        let count = self.query_count.fetch_add(1, Ordering::Relaxed) + 1;
+        format!("{}-{}", self.context.session_id(), count)
     }
 }
@@ -0,0 +1,14 @@
+use datafusion::common::config::ConfigExtension;
+use datafusion::common::extensions_options;
+
+extensions_options! {
+    /// Session options set by `SET dataharpoon.<key> = <value>`.
+    pub struct DataHarpoonOptions {
+        /// Calls to MCP servers a statement can make. Defaults to `limits.max_mcp_calls`.
+        pub max_mcp_calls: Option<usize>, default = None
+    }
+}
+
+impl ConfigExtension for DataHarpoonOptions {
+    const PREFIX: &'static str = "dataharpoon";
+}
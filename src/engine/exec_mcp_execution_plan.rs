@@ -0,0 +1,172 @@
+use crate::engine::udf::async_mcp_function::as_async_mcp_function;
+use datafusion::arrow::array::RecordBatch;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::common::{Result, exec_datafusion_err, internal_err};
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::ScalarUDF;
+use datafusion::physical_expr::{EquivalenceProperties, PhysicalExpr};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use futures::StreamExt;
+use std::any::Any;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+/// A call of exec_mcp or exec_mcp_batch taken out of an expression. Its result is the column of
+/// `name`.
+#[derive(Debug, Clone)]
+pub struct ExecMcpCall {
+    pub name: String,
+    pub function: Arc<ScalarUDF>,
+    pub args: Vec<Arc<dyn PhysicalExpr>>,
+}
+
+/// Appends the responses of the calls to the rows of the input. The calls are awaited on the
+/// runtime of the query, and dropping the stream cancels the calls in flight.
+#[derive(Debug)]
+pub struct ExecMcpExecutionPlan {
+    input: Arc<dyn ExecutionPlan>,
+    calls: Vec<ExecMcpCall>,
+    schema: SchemaRef,
+    plan_properties: PlanProperties,
+}
+
+impl ExecMcpExecutionPlan {
+    pub fn new(input: Arc<dyn ExecutionPlan>, calls: Vec<ExecMcpCall>) -> Self {
+        let input_schema = input.schema();
+        let fields = input_schema.fields().iter().cloned().chain(
+            calls
+                .iter()
+                .map(|call| Arc::new(Field::new(&call.name, DataType::Utf8, true))),
+        );
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields.collect::<Vec<_>>(),
+            input_schema.metadata().clone(),
+        ));
+
+        let input_properties = input.properties();
+        let plan_properties = PlanProperties::new(
+            EquivalenceProperties::new(schema.clone()),
+            input_properties.output_partitioning().clone(),
+            input_properties.emission_type,
+            input_properties.boundedness,
+        );
+
+        Self {
+            input,
+            calls,
+            schema,
+            plan_properties,
+        }
+    }
+
+    /// Calls estimated from the rows of the input, or None when the input doesn't know them.
+    pub fn estimate_calls(&self) -> Option<usize> {
+        let statistics = self.input.statistics().ok()?;
+        let rows = *statistics.num_rows.get_value()?;
+        let rows = self.input.fetch().map_or(rows, |fetch| rows.min(fetch));
+
+        let calls = self
+            .calls
+            .iter()
+            .filter_map(|call| {
+                as_async_mcp_function(&call.function)
+                    .map(|function| function.estimate_calls(&call.args, rows))
+            })
+            .sum();
+        Some(calls)
+    }
+
+    async fn append_responses(
+        batch: RecordBatch,
+        calls: &[ExecMcpCall],
+        schema: SchemaRef,
+    ) -> Result<RecordBatch> {
+        let mut columns = batch.columns().to_vec();
+        for call in calls {
+            let Some(function) = as_async_mcp_function(&call.function) else {
+                return internal_err!("{} doesn't call MCP tools", call.function.name());
+            };
+
+            let args = call
+                .args
+                .iter()
+                .map(|arg| arg.evaluate(&batch))
+                .collect::<Result<Vec<_>>>()?;
+            let responses = function.invoke_async(&args, batch.num_rows()).await?;
+            columns.push(responses);
+        }
+
+        RecordBatch::try_new(schema, columns)
+            .map_err(|e| exec_datafusion_err!("failed to append the responses. {}", e))
+    }
+}
+
+impl DisplayAs for ExecMcpExecutionPlan {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        let calls: Vec<_> = self
+            .calls
+            .iter()
+            .map(|call| {
+                let args: Vec<_> = call.args.iter().map(|arg| arg.to_string()).collect();
+                format!(
+                    "{}={}({})",
+                    call.name,
+                    call.function.name(),
+                    args.join(", ")
+                )
+            })
+            .collect();
+        write!(f, "ExecMcpExecutionPlan: {}", calls.join(", "))
+    }
+}
+
+impl ExecutionPlan for ExecMcpExecutionPlan {
+    fn name(&self) -> &str {
+        "ExecMcpExecutionPlan"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let [input] = children
+            .try_into()
+            .map_err(|_| exec_datafusion_err!("ExecMcpExecutionPlan takes exactly one input"))?;
+
+        Ok(Arc::new(Self::new(input, self.calls.clone())))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let input = self.input.execute(partition, context)?;
+        let calls = Arc::new(self.calls.clone());
+        let schema = self.schema.clone();
+
+        let stream = input.then(move |batch| {
+            let calls = calls.clone();
+            let schema = schema.clone();
+            async move { Self::append_responses(batch?, &calls, schema).await }
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream,
+        )))
+    }
+}
@@ -0,0 +1,134 @@
+use crate::engine::exec_mcp_execution_plan::{ExecMcpCall, ExecMcpExecutionPlan};
+use crate::engine::udf::async_mcp_function::as_async_mcp_function;
+use datafusion::common::Result;
+use datafusion::common::config::ConfigOptions;
+use datafusion::common::tree_node::{Transformed, TransformedResult, TreeNode, TreeNodeRecursion};
+use datafusion::physical_expr::expressions::{CaseExpr, Column};
+use datafusion::physical_expr::{PhysicalExpr, ScalarFunctionExpr};
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::projection::ProjectionExec;
+use std::sync::Arc;
+
+/// Input with the calls and the expressions reading them.
+type ExtractedCalls = (Arc<dyn ExecutionPlan>, Vec<Arc<dyn PhysicalExpr>>);
+
+/// Moves exec_mcp and exec_mcp_batch calls in projections and filters into `ExecMcpExecutionPlan` under them, so the
+/// calls are awaited rather than blocking in the scalar function.
+#[derive(Debug, Default)]
+pub struct ExecMcpRule {}
+
+impl PhysicalOptimizerRule for ExecMcpRule {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(|plan| {
+            if let Some(projection) = plan.as_any().downcast_ref::<ProjectionExec>() {
+                let (exprs, names): (Vec<_>, Vec<_>) = projection.expr().iter().cloned().unzip();
+                let Some((input, exprs)) = Self::extract_calls(projection.input(), exprs)? else {
+                    return Ok(Transformed::no(plan));
+                };
+
+                let exprs = exprs.into_iter().zip(names).collect();
+                return Ok(Transformed::yes(Arc::new(ProjectionExec::try_new(
+                    exprs, input,
+                )?)));
+            }
+
+            if let Some(filter) = plan.as_any().downcast_ref::<FilterExec>() {
+                let predicate = vec![filter.predicate().clone()];
+                let Some((input, mut predicate)) = Self::extract_calls(filter.input(), predicate)?
+                else {
+                    return Ok(Transformed::no(plan));
+                };
+
+                // Leaves out the responses, same as the input of the filter.
+                let projection = match filter.projection() {
+                    Some(projection) => projection.clone(),
+                    None => (0..filter.input().schema().fields().len()).collect(),
+                };
+                let filter = FilterExec::try_new(predicate.remove(0), input)?
+                    .with_default_selectivity(filter.default_selectivity())?
+                    .with_projection(Some(projection))?;
+                return Ok(Transformed::yes(Arc::new(filter)));
+            }
+
+            Ok(Transformed::no(plan))
+        })
+        .data()
+    }
+
+    fn name(&self) -> &str {
+        "exec_mcp"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+impl ExecMcpRule {
+    /// Replaces the calls in the expressions with the columns of `ExecMcpExecutionPlan`.
+    /// Returns None if there are no calls.
+    fn extract_calls(
+        input: &Arc<dyn ExecutionPlan>,
+        exprs: Vec<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Option<ExtractedCalls>> {
+        let input_len = input.schema().fields().len();
+        let mut calls: Vec<(Arc<dyn PhysicalExpr>, ExecMcpCall)> = vec![];
+
+        let exprs = exprs
+            .into_iter()
+            .map(|expr| {
+                expr.transform_down(|expr| {
+                    // The calls in a CASE branch or a short-circuiting function such as coalesce
+                    // are made only for some rows, so they are left to the function itself.
+                    if expr.as_any().is::<CaseExpr>() {
+                        return Ok(Transformed::new(expr, false, TreeNodeRecursion::Jump));
+                    }
+                    let Some(function) = expr.as_any().downcast_ref::<ScalarFunctionExpr>() else {
+                        return Ok(Transformed::no(expr));
+                    };
+                    if as_async_mcp_function(function.fun()).is_none() {
+                        let recursion = if function.fun().short_circuits() {
+                            TreeNodeRecursion::Jump
+                        } else {
+                            TreeNodeRecursion::Continue
+                        };
+                        return Ok(Transformed::new(expr, false, recursion));
+                    }
+
+                    let index = match calls.iter().position(|(e, _)| e == &expr) {
+                        Some(index) => index,
+                        None => {
+                            calls.push((
+                                expr.clone(),
+                                ExecMcpCall {
+                                    name: format!("__exec_mcp_{}", calls.len()),
+                                    function: Arc::new(function.fun().clone()),
+                                    args: function.args().to_vec(),
+                                },
+                            ));
+                            calls.len() - 1
+                        }
+                    };
+                    let column = Arc::new(Column::new(&calls[index].1.name, input_len + index));
+                    // Calls in the arguments are made by the function itself.
+                    Ok(Transformed::new(column, true, TreeNodeRecursion::Jump))
+                })
+                .data()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if calls.is_empty() {
+            return Ok(None);
+        }
+
+        let calls = calls.into_iter().map(|(_, call)| call).collect();
+        let input = Arc::new(ExecMcpExecutionPlan::new(input.clone(), calls));
+        Ok(Some((input, exprs)))
+    }
+}
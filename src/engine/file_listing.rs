@@ -0,0 +1,56 @@
+use datafusion::common::{DataFusionError, Result};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::execution::SessionState;
+use futures::TryStreamExt;
+use object_store::{ObjectMeta, ObjectStore};
+use std::sync::Arc;
+
+/// Lists the files of the url, a file, directory or glob, in the path order.
+pub async fn list_files(
+    state: &SessionState,
+    table_url: &ListingTableUrl,
+) -> Result<(Arc<dyn ObjectStore>, Vec<ObjectMeta>)> {
+    let store = state.runtime_env().object_store(table_url.object_store())?;
+
+    let mut metas = table_url
+        .list_all_files(state, store.as_ref(), "")
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?;
+    metas.sort_by(|a, b| a.location.cmp(&b.location));
+
+    Ok((store, metas))
+}
+
+pub async fn read_bytes(store: &Arc<dyn ObjectStore>, meta: &ObjectMeta) -> Result<Vec<u8>> {
+    let bytes = store
+        .get(&meta.location)
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))?
+        .bytes()
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+    Ok(bytes.to_vec())
+}
+
+pub async fn read_head(
+    store: &Arc<dyn ObjectStore>,
+    meta: &ObjectMeta,
+    size: u64,
+) -> Result<Vec<u8>> {
+    let bytes = store
+        .get_range(&meta.location, 0..meta.size.min(size))
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+    Ok(bytes.to_vec())
+}
+
+/// Returns the path for local files, and the url for the others.
+pub fn display_location(table_url: &ListingTableUrl, meta: &ObjectMeta) -> String {
+    match table_url.scheme() {
+        "file" => format!("/{}", meta.location),
+        _ => format!("{}{}", table_url.object_store().as_str(), meta.location),
+    }
+}
@@ -0,0 +1,173 @@
+use chrono::NaiveDate;
+use datafusion::arrow::datatypes::{DataType, Schema};
+use datafusion::catalog::TableProvider;
+use datafusion::common::{Result, plan_err};
+use datafusion::datasource::listing::{ListingTable, ListingTableConfig};
+use datafusion::execution::SessionState;
+use futures::TryStreamExt;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// Types the Hive-style partition columns (e.g. `date=2025-06-01/`) of the listing table from their
+/// values, instead of strings. With `discover`, the partition columns are found from the paths
+/// when the table has none.
+///
+/// Filters on the partition columns skip the files not matching them.
+pub async fn with_typed_partitions(
+    state: &SessionState,
+    table: Arc<dyn TableProvider>,
+    discover: bool,
+) -> Result<Arc<dyn TableProvider>> {
+    let Some(listing_table) = table.as_any().downcast_ref::<ListingTable>() else {
+        return Ok(table);
+    };
+    let options = listing_table.options();
+    let table_paths = listing_table.table_paths();
+    if options.table_partition_cols.is_empty() && !discover {
+        return Ok(table);
+    }
+
+    let partition_values = list_partition_values(state, listing_table).await?;
+    let partition_cols = if options.table_partition_cols.is_empty() && discover {
+        partition_values
+            .iter()
+            .map(|(name, _)| (name.clone(), DataType::Utf8))
+            .collect()
+    } else {
+        options.table_partition_cols.clone()
+    };
+    if partition_cols.is_empty() {
+        return Ok(table);
+    }
+
+    let typed_partition_cols = partition_cols
+        .into_iter()
+        .map(|(name, data_type)| {
+            // Keep the types given by the schema.
+            if !is_string_type(&data_type) {
+                return (name, data_type);
+            }
+            let data_type = partition_values
+                .iter()
+                .find(|(key, _)| key == &name)
+                .map(|(_, values)| infer_type(values))
+                .unwrap_or(DataType::Utf8);
+            (name, data_type)
+        })
+        .collect::<Vec<_>>();
+
+    // The partition columns follow the columns of the files.
+    let table_schema = listing_table.schema();
+    let file_field_count = table_schema.fields().len() - options.table_partition_cols.len();
+    let file_schema = Schema::new(table_schema.fields()[..file_field_count].to_vec());
+
+    let options = options
+        .clone()
+        .with_table_partition_cols(typed_partition_cols);
+    let config = ListingTableConfig::new_with_multi_paths(table_paths.clone())
+        .with_listing_options(options)
+        .with_schema(Arc::new(file_schema));
+    let table = ListingTable::try_new(config)?
+        .with_cache(state.runtime_env().cache_manager.get_file_statistic_cache());
+
+    Ok(Arc::new(table))
+}
+
+/// Parses the partition keys and values of the path segments. e.g. `a=1/b=x/f.csv`
+fn parse_partitions<'a>(segments: impl Iterator<Item = &'a str>) -> Vec<(String, String)> {
+    let mut segments = segments.collect::<Vec<_>>();
+    // The last one is the file name.
+    segments.pop();
+
+    segments
+        .into_iter()
+        .map_while(|segment| {
+            segment
+                .split_once('=')
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Lists the partition keys and their distinct values in the path order.
+async fn list_partition_values(
+    state: &SessionState,
+    listing_table: &ListingTable,
+) -> Result<Vec<(String, BTreeSet<String>)>> {
+    let options = listing_table.options();
+    let mut partition_values: Option<Vec<(String, BTreeSet<String>)>> = None;
+
+    for table_path in listing_table.table_paths() {
+        if !table_path.is_collection() {
+            continue;
+        }
+        let store = state.runtime_env().object_store(table_path)?;
+        let metas = table_path
+            .list_all_files(state, store.as_ref(), &options.file_extension)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        for meta in metas {
+            let Some(segments) = table_path.strip_prefix(&meta.location) else {
+                continue;
+            };
+            let partitions = parse_partitions(segments);
+
+            let values = partition_values.get_or_insert_with(|| {
+                partitions
+                    .iter()
+                    .map(|(key, _)| (key.clone(), BTreeSet::new()))
+                    .collect()
+            });
+            let keys_match = values.len() == partitions.len()
+                && values
+                    .iter()
+                    .zip(&partitions)
+                    .all(|((a, _), (b, _))| a == b);
+            if !keys_match {
+                return plan_err!(
+                    "partition keys must be the same for all files in {}. Found: {}",
+                    table_path,
+                    meta.location
+                );
+            }
+
+            for ((_, values), (_, value)) in values.iter_mut().zip(partitions) {
+                values.insert(value);
+            }
+        }
+    }
+
+    Ok(partition_values.unwrap_or_default())
+}
+
+fn is_string_type(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => true,
+        DataType::Dictionary(_, value_type) => is_string_type(value_type),
+        _ => false,
+    }
+}
+
+/// Infers the narrowest type for all values: integer, float, date (YYYY-MM-DD), boolean or string.
+fn infer_type(values: &BTreeSet<String>) -> DataType {
+    if values.is_empty() {
+        return DataType::Utf8;
+    }
+
+    if values.iter().all(|v| v.parse::<i64>().is_ok()) {
+        DataType::Int64
+    } else if values.iter().all(|v| v.parse::<f64>().is_ok()) {
+        DataType::Float64
+    } else if values
+        .iter()
+        .all(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").is_ok())
+    {
+        DataType::Date32
+    } else if values.iter().all(|v| v == "true" || v == "false") {
+        DataType::Boolean
+    } else {
+        DataType::Utf8
+    }
+}
@@ -1,3 +1,7 @@
 pub mod information_schema_provider;
+mod materialized_views_table_provider;
+mod mcp_calls_table_provider;
 mod mcp_servers_table_provider;
 mod mcp_tools_table_provider;
+mod query_history_table_provider;
+mod sources_table;
@@ -1,10 +1,24 @@
 use crate::config::mcp_server_config::McpServerConfig;
+use crate::config::source_config::SourceConfig;
+use crate::engine::information_schema::materialized_views_table_provider::{
+    MATERIALIZED_VIEWS_TABLE_NAME, MaterializedViewsTableProvider,
+};
+use crate::engine::information_schema::mcp_calls_table_provider::{
+    MCP_CALLS_TABLE_NAME, McpCallsTableProvider,
+};
 use crate::engine::information_schema::mcp_servers_table_provider::{
     MCP_SERVERS_TABLE_NAME, McpServersTableProvider,
 };
 use crate::engine::information_schema::mcp_tools_table_provider::{
     MCP_TOOLS_TABLE_NAME, McpToolsTableProvider,
 };
+use crate::engine::information_schema::query_history_table_provider::{
+    QUERY_HISTORY_TABLE_NAME, QueryHistoryTableProvider,
+};
+use crate::engine::information_schema::sources_table::{SOURCES_TABLE_NAME, create_sources_table};
+use crate::engine::materialized_view::materialized_view_store::MaterializedViewStore;
+use crate::engine::mcp_call_log::McpCallLog;
+use crate::engine::query_history::QueryHistory;
 use async_trait::async_trait;
 use dashmap::DashMap;
 use datafusion::catalog::{SchemaProvider, TableProvider};
@@ -20,14 +34,30 @@ pub struct InformationSchemaProvider {
 }
 
 impl InformationSchemaProvider {
-    pub fn new(mcp_servers: DashMap<String, Arc<McpServerConfig>>) -> Self {
+    pub fn new(
+        mcp_servers: DashMap<String, Arc<McpServerConfig>>,
+        mcp_call_log: Arc<McpCallLog>,
+        query_history: Arc<QueryHistory>,
+        sources: &[SourceConfig],
+        materialized_views: Arc<MaterializedViewStore>,
+    ) -> Self {
         Self {
-            tables: Self::initialize_tables(mcp_servers),
+            tables: Self::initialize_tables(
+                mcp_servers,
+                mcp_call_log,
+                query_history,
+                sources,
+                materialized_views,
+            ),
         }
     }
 
     fn initialize_tables(
         mcp_servers: DashMap<String, Arc<McpServerConfig>>,
+        mcp_call_log: Arc<McpCallLog>,
+        query_history: Arc<QueryHistory>,
+        sources: &[SourceConfig],
+        materialized_views: Arc<MaterializedViewStore>,
     ) -> DashMap<String, Arc<dyn TableProvider>> {
         let tables = DashMap::<String, Arc<dyn TableProvider>>::new();
         tables.insert(
@@ -38,6 +68,22 @@ impl InformationSchemaProvider {
             MCP_SERVERS_TABLE_NAME.to_string(),
             Arc::new(McpServersTableProvider::new(mcp_servers)),
         );
+        tables.insert(
+            MCP_CALLS_TABLE_NAME.to_string(),
+            Arc::new(McpCallsTableProvider::new(mcp_call_log)),
+        );
+        tables.insert(
+            QUERY_HISTORY_TABLE_NAME.to_string(),
+            Arc::new(QueryHistoryTableProvider::new(query_history)),
+        );
+        tables.insert(
+            SOURCES_TABLE_NAME.to_string(),
+            create_sources_table(sources),
+        );
+        tables.insert(
+            MATERIALIZED_VIEWS_TABLE_NAME.to_string(),
+            Arc::new(MaterializedViewsTableProvider::new(materialized_views)),
+        );
 
         tables
     }
@@ -0,0 +1,99 @@
+use crate::engine::materialized_view::materialized_view_store::MaterializedViewStore;
+use async_trait::async_trait;
+use datafusion::arrow::array::{
+    ArrayRef, Int64Array, RecordBatch, StringArray, TimestampMillisecondArray, UInt64Array,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::catalog::{Session, TableProvider};
+use datafusion::common::Result;
+use datafusion::datasource::{MemTable, TableType};
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::ExecutionPlan;
+use std::any::Any;
+use std::sync::Arc;
+
+pub(super) const MATERIALIZED_VIEWS_TABLE_NAME: &str = "materialized_views";
+
+/// Lists the materialized views with the time their snapshots were taken.
+#[derive(Debug)]
+pub(super) struct MaterializedViewsTableProvider {
+    store: Arc<MaterializedViewStore>,
+}
+
+impl MaterializedViewsTableProvider {
+    pub(super) fn new(store: Arc<MaterializedViewStore>) -> Self {
+        Self { store }
+    }
+
+    fn create_batch(&self) -> Result<RecordBatch> {
+        let metadata: Vec<_> = self
+            .store
+            .list()
+            .iter()
+            .map(|view| (view.metadata(), view.snapshot_file().display().to_string()))
+            .collect();
+
+        let names: Vec<_> = metadata.iter().map(|(m, _)| m.name.clone()).collect();
+        let sqls: Vec<_> = metadata.iter().map(|(m, _)| m.sql.clone()).collect();
+        let ttl_secs: Vec<_> = metadata.iter().map(|(m, _)| m.ttl_secs).collect();
+        let refreshed_ats: Vec<_> = metadata
+            .iter()
+            .map(|(m, _)| m.refreshed_at.map(|t| t.timestamp_millis()))
+            .collect();
+        let expires_ats: Vec<_> = metadata
+            .iter()
+            .map(|(m, _)| m.expires_at().map(|t| t.timestamp_millis()))
+            .collect();
+        let row_counts: Vec<_> = metadata.iter().map(|(m, _)| m.row_count).collect();
+        let files: Vec<_> = metadata.iter().map(|(_, f)| f.clone()).collect();
+
+        Ok(RecordBatch::try_new(
+            self.schema(),
+            vec![
+                Arc::new(StringArray::from(names)) as ArrayRef,
+                Arc::new(StringArray::from(sqls)),
+                Arc::new(UInt64Array::from(ttl_secs)),
+                Arc::new(TimestampMillisecondArray::from(refreshed_ats).with_timezone("UTC")),
+                Arc::new(TimestampMillisecondArray::from(expires_ats).with_timezone("UTC")),
+                Arc::new(Int64Array::from(row_counts)),
+                Arc::new(StringArray::from(files)),
+            ],
+        )?)
+    }
+}
+
+#[async_trait]
+impl TableProvider for MaterializedViewsTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        let timestamp = DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()));
+        Arc::new(Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("sql", DataType::Utf8, true),
+            Field::new("ttl_secs", DataType::UInt64, true),
+            Field::new("refreshed_at", timestamp.clone(), true),
+            Field::new("expires_at", timestamp, true),
+            Field::new("row_count", DataType::Int64, true),
+            Field::new("file", DataType::Utf8, true),
+        ]))
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    async fn scan(
+        &self,
+        state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let batch = self.create_batch()?;
+        let table = MemTable::try_new(self.schema(), vec![vec![batch]])?;
+        table.scan(state, projection, filters, limit).await
+    }
+}
@@ -0,0 +1,193 @@
+use crate::engine::mcp_call_log::{McpCallLog, McpCallRecord};
+use async_trait::async_trait;
+use datafusion::arrow::array::{
+    ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray, TimestampMillisecondArray,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::catalog::{Session, TableProvider};
+use datafusion::common::{Result, exec_err, project_schema};
+use datafusion::datasource::TableType;
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::Expr;
+use datafusion::physical_expr::{EquivalenceProperties, Partitioning};
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::memory::MemoryStream;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+pub(super) const MCP_CALLS_TABLE_NAME: &str = "mcp_calls";
+
+#[derive(Debug)]
+pub(super) struct McpCallsTableProvider {
+    call_log: Arc<McpCallLog>,
+}
+
+impl McpCallsTableProvider {
+    pub(super) fn new(call_log: Arc<McpCallLog>) -> Self {
+        Self { call_log }
+    }
+}
+
+#[async_trait]
+impl TableProvider for McpCallsTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        let schema = Schema::new(vec![
+            Arc::new(Field::new(
+                "called_at",
+                DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+                true,
+            )),
+            Arc::new(Field::new("query_id", DataType::Utf8, true)),
+            Arc::new(Field::new("server_name", DataType::Utf8, true)),
+            Arc::new(Field::new("tool_name", DataType::Utf8, true)),
+            Arc::new(Field::new("arguments", DataType::Utf8, true)),
+            Arc::new(Field::new("latency_ms", DataType::Int64, true)),
+            Arc::new(Field::new("response_bytes", DataType::Int64, true)),
+            Arc::new(Field::new("cache_hit", DataType::Boolean, true)),
+            Arc::new(Field::new("error", DataType::Utf8, true)),
+        ]);
+
+        Arc::new(schema)
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(McpCallsTableExecutionPlan::new(
+            projection,
+            self.schema(),
+            self.call_log.clone(),
+        )))
+    }
+}
+
+#[derive(Debug)]
+struct McpCallsTableExecutionPlan {
+    plan_properties: PlanProperties,
+    call_log: Arc<McpCallLog>,
+}
+
+impl McpCallsTableExecutionPlan {
+    fn new(projection: Option<&Vec<usize>>, schema: SchemaRef, call_log: Arc<McpCallLog>) -> Self {
+        let projected_schema = project_schema(&schema, projection).unwrap();
+        let plan_properties = Self::compute_properties(projected_schema);
+        Self {
+            plan_properties,
+            call_log,
+        }
+    }
+
+    fn compute_properties(schema: SchemaRef) -> PlanProperties {
+        let eq_properties = EquivalenceProperties::new(schema);
+        PlanProperties::new(
+            eq_properties,
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        )
+    }
+}
+
+impl DisplayAs for McpCallsTableExecutionPlan {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "McpCallsTableExecutionPlan")
+    }
+}
+
+impl ExecutionPlan for McpCallsTableExecutionPlan {
+    fn name(&self) -> &str {
+        "McpCallsTableExecutionPlan"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        _partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema();
+
+        let call_columns = Self::call_columns(&self.call_log.records());
+
+        let mut columns = Vec::<ArrayRef>::new();
+        for field in schema.fields.iter() {
+            let Some(column) = call_columns.get(field.name().as_str()) else {
+                return exec_err!("unknown column found. column: {}", field.name());
+            };
+            columns.push(column.clone());
+        }
+
+        let Ok(rec) = RecordBatch::try_new(schema.clone(), columns) else {
+            return exec_err!("failed to create RecordBatch. schema: {:?}", self.schema());
+        };
+
+        let stream = Box::pin(MemoryStream::try_new(vec![rec], self.schema(), None)?);
+        Ok(stream)
+    }
+}
+
+impl McpCallsTableExecutionPlan {
+    fn call_columns(records: &[McpCallRecord]) -> HashMap<&'static str, ArrayRef> {
+        let called_ats: Vec<_> = records
+            .iter()
+            .map(|r| r.called_at.timestamp_millis())
+            .collect();
+        let query_ids: Vec<_> = records.iter().map(|r| r.query_id.clone()).collect();
+        let server_names: Vec<_> = records.iter().map(|r| r.server_name.clone()).collect();
+        let tool_names: Vec<_> = records.iter().map(|r| r.tool_name.clone()).collect();
+        let arguments: Vec<_> = records.iter().map(|r| r.arguments.clone()).collect();
+        let latencies: Vec<_> = records.iter().map(|r| r.latency_ms).collect();
+        let response_bytes: Vec<_> = records.iter().map(|r| r.response_bytes).collect();
+        let cache_hits: Vec<_> = records.iter().map(|r| r.cache_hit).collect();
+        let errors: Vec<_> = records.iter().map(|r| r.error.clone()).collect();
+
+        HashMap::<&str, ArrayRef>::from([
+            (
+                "called_at",
+                Arc::new(TimestampMillisecondArray::from(called_ats).with_timezone("UTC"))
+                    as ArrayRef,
+            ),
+            ("query_id", Arc::new(StringArray::from(query_ids))),
+            ("server_name", Arc::new(StringArray::from(server_names))),
+            ("tool_name", Arc::new(StringArray::from(tool_names))),
+            ("arguments", Arc::new(StringArray::from(arguments))),
+            ("latency_ms", Arc::new(Int64Array::from(latencies))),
+            ("response_bytes", Arc::new(Int64Array::from(response_bytes))),
+            ("cache_hit", Arc::new(BooleanArray::from(cache_hits))),
+            ("error", Arc::new(StringArray::from(errors))),
+        ])
+    }
+}
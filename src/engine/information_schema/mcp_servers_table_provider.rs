@@ -1,7 +1,11 @@
 use crate::config::mcp_server_config::McpServerConfig;
+use crate::infra::mcp_client::McpClient;
+use crate::util::redact::redact_args;
 use async_trait::async_trait;
 use dashmap::DashMap;
-use datafusion::arrow::array::{ArrayRef, ListBuilder, RecordBatch, StringArray, StringBuilder};
+use datafusion::arrow::array::{
+    ArrayRef, BooleanArray, Int64Array, ListBuilder, RecordBatch, StringArray, StringBuilder,
+};
 use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use datafusion::catalog::{Session, TableProvider};
 use datafusion::common::{Result, exec_err, project_schema};
@@ -11,14 +15,36 @@ use datafusion::logical_expr::Expr;
 use datafusion::physical_expr::{EquivalenceProperties, Partitioning};
 use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
 use datafusion::physical_plan::memory::MemoryStream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use futures::future::join_all;
+use futures::stream;
+use rmcp::model::ServerInfo;
 use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::Formatter;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 pub(super) const MCP_SERVERS_TABLE_NAME: &str = "mcp_servers";
 
+// Columns requiring to connect to the servers. Servers are connected only when one of them is selected.
+const LIVE_COLUMNS: [&str; 11] = [
+    "reachable",
+    "protocol_version",
+    "server_info_name",
+    "server_info_version",
+    "supports_tools",
+    "supports_resources",
+    "supports_prompts",
+    "supports_logging",
+    "instructions",
+    "startup_latency_ms",
+    "last_error",
+];
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Debug)]
 pub(super) struct McpServersTableProvider {
     mcp_servers: DashMap<String, Arc<McpServerConfig>>,
@@ -45,6 +71,17 @@ impl TableProvider for McpServersTableProvider {
                 DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true))),
                 true,
             )),
+            Arc::new(Field::new("reachable", DataType::Boolean, true)),
+            Arc::new(Field::new("protocol_version", DataType::Utf8, true)),
+            Arc::new(Field::new("server_info_name", DataType::Utf8, true)),
+            Arc::new(Field::new("server_info_version", DataType::Utf8, true)),
+            Arc::new(Field::new("supports_tools", DataType::Boolean, true)),
+            Arc::new(Field::new("supports_resources", DataType::Boolean, true)),
+            Arc::new(Field::new("supports_prompts", DataType::Boolean, true)),
+            Arc::new(Field::new("supports_logging", DataType::Boolean, true)),
+            Arc::new(Field::new("instructions", DataType::Utf8, true)),
+            Arc::new(Field::new("startup_latency_ms", DataType::Int64, true)),
+            Arc::new(Field::new("last_error", DataType::Utf8, true)),
         ]);
 
         Arc::new(schema)
@@ -136,8 +173,76 @@ impl ExecutionPlan for McpServersTableExecutionPlan {
         _context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         let schema = self.schema();
+        let configs: Vec<Arc<McpServerConfig>> =
+            self.mcp_servers.iter().map(|v| v.value().clone()).collect();
+
+        let requires_probe = schema
+            .fields
+            .iter()
+            .any(|f| LIVE_COLUMNS.contains(&f.name().as_str()));
+        if !requires_probe {
+            let rec = Self::build_record_batch(schema.clone(), &configs, None)?;
+            let stream = Box::pin(MemoryStream::try_new(vec![rec], schema, None)?);
+            return Ok(stream);
+        }
+
+        let stream_schema = schema.clone();
+        let future = async move {
+            let probes = join_all(configs.iter().map(|c| probe_server(c.clone()))).await;
+            Self::build_record_batch(schema, &configs, Some(&probes))
+        };
 
-        let server_columns = self.server_columns();
+        let stream = RecordBatchStreamAdapter::new(stream_schema, stream::once(future));
+        Ok(Box::pin(stream))
+    }
+}
+
+struct ProbeResult {
+    server_info: Option<ServerInfo>,
+    startup_latency: Duration,
+    error: Option<String>,
+}
+
+async fn probe_server(config: Arc<McpServerConfig>) -> ProbeResult {
+    let cli = McpClient::new(config.clone());
+
+    let started_at = Instant::now();
+    let result = tokio::time::timeout(PROBE_TIMEOUT, cli.initialize()).await;
+    let startup_latency = started_at.elapsed();
+
+    match result {
+        Ok(Ok(server_info)) => ProbeResult {
+            server_info: Some(server_info),
+            startup_latency,
+            error: None,
+        },
+        Ok(Err(e)) => ProbeResult {
+            server_info: None,
+            startup_latency,
+            error: Some(e.to_string()),
+        },
+        Err(_) => ProbeResult {
+            server_info: None,
+            startup_latency,
+            error: Some(format!(
+                "mcp-server({}) did not respond within {} seconds",
+                config.name,
+                PROBE_TIMEOUT.as_secs()
+            )),
+        },
+    }
+}
+
+impl McpServersTableExecutionPlan {
+    fn build_record_batch(
+        schema: SchemaRef,
+        configs: &[Arc<McpServerConfig>],
+        probes: Option<&Vec<ProbeResult>>,
+    ) -> Result<RecordBatch> {
+        let mut server_columns = Self::server_columns(configs);
+        if let Some(probes) = probes {
+            server_columns.extend(Self::live_columns(probes));
+        }
 
         let mut columns = Vec::<ArrayRef>::new();
         for field in schema.fields.iter() {
@@ -148,19 +253,13 @@ impl ExecutionPlan for McpServersTableExecutionPlan {
         }
 
         let Ok(rec) = RecordBatch::try_new(schema.clone(), columns) else {
-            return exec_err!("failed to create RecordBatch. schema: {:?}", self.schema());
+            return exec_err!("failed to create RecordBatch. schema: {:?}", schema);
         };
 
-        let stream = Box::pin(MemoryStream::try_new(vec![rec], self.schema(), None)?);
-        Ok(stream)
+        Ok(rec)
     }
-}
-
-impl McpServersTableExecutionPlan {
-    fn server_columns(&self) -> HashMap<&str, ArrayRef> {
-        let configs: Vec<Arc<McpServerConfig>> =
-            self.mcp_servers.iter().map(|v| v.value().clone()).collect();
 
+    fn server_columns(configs: &[Arc<McpServerConfig>]) -> HashMap<&'static str, ArrayRef> {
         let names: Vec<_> = configs.iter().map(|c| c.name.clone()).collect();
         let names_array = StringArray::from(names);
 
@@ -170,7 +269,7 @@ impl McpServersTableExecutionPlan {
         let args_builder = StringBuilder::new();
         let mut args_list_builder = ListBuilder::new(args_builder);
         for config in configs {
-            for arg in config.args.iter() {
+            for arg in redact_args(&config.args).iter() {
                 args_list_builder.values().append_value(arg);
             }
             args_list_builder.append(true);
@@ -184,4 +283,91 @@ impl McpServersTableExecutionPlan {
 
         result_arrays
     }
+
+    fn live_columns(probes: &[ProbeResult]) -> HashMap<&'static str, ArrayRef> {
+        let infos: Vec<_> = probes.iter().map(|p| p.server_info.as_ref()).collect();
+
+        let reachable: Vec<_> = infos.iter().map(|i| Some(i.is_some())).collect();
+        let protocol_versions: Vec<_> = infos
+            .iter()
+            .map(|i| {
+                i.and_then(|i| serde_json::to_value(&i.protocol_version).ok())
+                    .and_then(|v| v.as_str().map(|v| v.to_string()))
+            })
+            .collect();
+        let server_info_names: Vec<_> = infos
+            .iter()
+            .map(|i| i.map(|i| i.server_info.name.clone()))
+            .collect();
+        let server_info_versions: Vec<_> = infos
+            .iter()
+            .map(|i| i.map(|i| i.server_info.version.clone()))
+            .collect();
+        let supports_tools: Vec<_> = infos
+            .iter()
+            .map(|i| i.map(|i| i.capabilities.tools.is_some()))
+            .collect();
+        let supports_resources: Vec<_> = infos
+            .iter()
+            .map(|i| i.map(|i| i.capabilities.resources.is_some()))
+            .collect();
+        let supports_prompts: Vec<_> = infos
+            .iter()
+            .map(|i| i.map(|i| i.capabilities.prompts.is_some()))
+            .collect();
+        let supports_logging: Vec<_> = infos
+            .iter()
+            .map(|i| i.map(|i| i.capabilities.logging.is_some()))
+            .collect();
+        let instructions: Vec<_> = infos
+            .iter()
+            .map(|i| i.and_then(|i| i.instructions.clone()))
+            .collect();
+        let startup_latencies: Vec<_> = probes
+            .iter()
+            .map(|p| Some(p.startup_latency.as_millis() as i64))
+            .collect();
+        let errors: Vec<_> = probes.iter().map(|p| p.error.clone()).collect();
+
+        HashMap::<&str, ArrayRef>::from([
+            (
+                "reachable",
+                Arc::new(BooleanArray::from(reachable)) as ArrayRef,
+            ),
+            (
+                "protocol_version",
+                Arc::new(StringArray::from(protocol_versions)),
+            ),
+            (
+                "server_info_name",
+                Arc::new(StringArray::from(server_info_names)),
+            ),
+            (
+                "server_info_version",
+                Arc::new(StringArray::from(server_info_versions)),
+            ),
+            (
+                "supports_tools",
+                Arc::new(BooleanArray::from(supports_tools)),
+            ),
+            (
+                "supports_resources",
+                Arc::new(BooleanArray::from(supports_resources)),
+            ),
+            (
+                "supports_prompts",
+                Arc::new(BooleanArray::from(supports_prompts)),
+            ),
+            (
+                "supports_logging",
+                Arc::new(BooleanArray::from(supports_logging)),
+            ),
+            ("instructions", Arc::new(StringArray::from(instructions))),
+            (
+                "startup_latency_ms",
+                Arc::new(Int64Array::from(startup_latencies)),
+            ),
+            ("last_error", Arc::new(StringArray::from(errors))),
+        ])
+    }
 }
@@ -2,7 +2,7 @@ use crate::config::mcp_server_config::McpServerConfig;
 use crate::infra::mcp_client::McpClient;
 use async_trait::async_trait;
 use dashmap::DashMap;
-use datafusion::arrow::array::{ArrayRef, RecordBatch, StringArray};
+use datafusion::arrow::array::{ArrayRef, BooleanArray, RecordBatch, StringArray};
 use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use datafusion::catalog::{Session, TableProvider};
 use datafusion::common::{Result, ScalarValue, exec_err, project_schema};
@@ -49,6 +49,10 @@ impl TableProvider for McpToolsTableProvider {
             Arc::new(Field::new("tool_name", DataType::Utf8, true)),
             Arc::new(Field::new("description", DataType::Utf8, true)),
             Arc::new(Field::new("input_schema", DataType::Utf8, true)),
+            Arc::new(Field::new("read_only_hint", DataType::Boolean, true)),
+            Arc::new(Field::new("destructive_hint", DataType::Boolean, true)),
+            Arc::new(Field::new("idempotent_hint", DataType::Boolean, true)),
+            Arc::new(Field::new("open_world_hint", DataType::Boolean, true)),
         ]);
 
         Arc::new(schema)
@@ -215,7 +219,7 @@ struct McpToolsRecordBatchStream {
     server_name_filters: Option<Vec<String>>,
 
     polled_index: usize,
-    current_server_name: Option<String>,
+    current_config: Option<Arc<McpServerConfig>>,
     current_listing_future: Option<Pin<Box<dyn Future<Output = Result<Vec<Tool>>> + Send>>>,
 }
 
@@ -230,7 +234,7 @@ impl McpToolsRecordBatchStream {
             server_configs,
             server_name_filters,
             polled_index: 0,
-            current_server_name: None,
+            current_config: None,
             current_listing_future: None,
         }
     }
@@ -259,7 +263,7 @@ impl Stream for McpToolsRecordBatchStream {
                 });
             };
 
-            self.current_server_name = Some(next_config.name.clone());
+            self.current_config = Some(next_config.clone());
             self.current_listing_future = Some(future());
         }
 
@@ -278,10 +282,18 @@ impl Stream for McpToolsRecordBatchStream {
         };
         self.current_listing_future = None;
 
+        let Some(current_config) = self.current_config.clone() else {
+            return Ready(Some(exec_err!("no mcp-server is being listed")));
+        };
+        let tools: Vec<_> = tools
+            .into_iter()
+            .filter(|tool| current_config.is_tool_allowed(&tool.name))
+            .collect();
+
         let tool_names: Vec<_> = tools.iter().map(|tool| tool.name.to_string()).collect();
         let descriptions: Vec<_> = tools
             .iter()
-            .map(|tool| tool.description.to_string())
+            .map(|tool| tool.description.as_ref().map(|d| d.to_string()))
             .collect();
         let input_schemas: Vec<_> = tools
             .iter()
@@ -289,18 +301,51 @@ impl Stream for McpToolsRecordBatchStream {
                 serde_json::to_string(&tool.input_schema).map_or_else(|_e| None, |v| Some(v))
             })
             .collect();
+        let annotations: Vec<_> = tools.iter().map(|tool| tool.annotations.as_ref()).collect();
+        let read_only_hints: Vec<_> = annotations
+            .iter()
+            .map(|a| a.and_then(|a| a.read_only_hint))
+            .collect();
+        let destructive_hints: Vec<_> = annotations
+            .iter()
+            .map(|a| a.and_then(|a| a.destructive_hint))
+            .collect();
+        let idempotent_hints: Vec<_> = annotations
+            .iter()
+            .map(|a| a.and_then(|a| a.idempotent_hint))
+            .collect();
+        let open_world_hints: Vec<_> = annotations
+            .iter()
+            .map(|a| a.and_then(|a| a.open_world_hint))
+            .collect();
 
         let result_arrays = HashMap::<&str, ArrayRef>::from([
             (
                 "server_name",
                 Arc::new(StringArray::from(vec![
-                    self.current_server_name.clone();
+                    current_config.name.clone();
                     tools.len()
                 ])) as ArrayRef,
             ),
             ("tool_name", Arc::new(StringArray::from(tool_names))),
             ("description", Arc::new(StringArray::from(descriptions))),
             ("input_schema", Arc::new(StringArray::from(input_schemas))),
+            (
+                "read_only_hint",
+                Arc::new(BooleanArray::from(read_only_hints)),
+            ),
+            (
+                "destructive_hint",
+                Arc::new(BooleanArray::from(destructive_hints)),
+            ),
+            (
+                "idempotent_hint",
+                Arc::new(BooleanArray::from(idempotent_hints)),
+            ),
+            (
+                "open_world_hint",
+                Arc::new(BooleanArray::from(open_world_hints)),
+            ),
         ]);
 
         let mut columns = Vec::<ArrayRef>::new();
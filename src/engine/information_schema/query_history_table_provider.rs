@@ -0,0 +1,196 @@
+use crate::engine::query_history::{QueryHistory, QueryHistoryRecord};
+use async_trait::async_trait;
+use datafusion::arrow::array::{
+    ArrayRef, Int64Array, RecordBatch, StringArray, TimestampMillisecondArray,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::catalog::{Session, TableProvider};
+use datafusion::common::{Result, exec_err, project_schema};
+use datafusion::datasource::TableType;
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::Expr;
+use datafusion::physical_expr::{EquivalenceProperties, Partitioning};
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::memory::MemoryStream;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+pub(super) const QUERY_HISTORY_TABLE_NAME: &str = "query_history";
+
+#[derive(Debug)]
+pub(super) struct QueryHistoryTableProvider {
+    query_history: Arc<QueryHistory>,
+}
+
+impl QueryHistoryTableProvider {
+    pub(super) fn new(query_history: Arc<QueryHistory>) -> Self {
+        Self { query_history }
+    }
+}
+
+#[async_trait]
+impl TableProvider for QueryHistoryTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        let schema = Schema::new(vec![
+            Arc::new(Field::new("query_id", DataType::Utf8, true)),
+            Arc::new(Field::new("source", DataType::Utf8, true)),
+            Arc::new(Field::new("sql", DataType::Utf8, true)),
+            Arc::new(Field::new(
+                "started_at",
+                DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+                true,
+            )),
+            Arc::new(Field::new("duration_ms", DataType::Int64, true)),
+            Arc::new(Field::new("row_count", DataType::Int64, true)),
+            Arc::new(Field::new("status", DataType::Utf8, true)),
+            Arc::new(Field::new("error_message", DataType::Utf8, true)),
+        ]);
+
+        Arc::new(schema)
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(QueryHistoryTableExecutionPlan::new(
+            projection,
+            self.schema(),
+            self.query_history.clone(),
+        )))
+    }
+}
+
+#[derive(Debug)]
+struct QueryHistoryTableExecutionPlan {
+    plan_properties: PlanProperties,
+    query_history: Arc<QueryHistory>,
+}
+
+impl QueryHistoryTableExecutionPlan {
+    fn new(
+        projection: Option<&Vec<usize>>,
+        schema: SchemaRef,
+        query_history: Arc<QueryHistory>,
+    ) -> Self {
+        let projected_schema = project_schema(&schema, projection).unwrap();
+        let plan_properties = Self::compute_properties(projected_schema);
+        Self {
+            plan_properties,
+            query_history,
+        }
+    }
+
+    fn compute_properties(schema: SchemaRef) -> PlanProperties {
+        let eq_properties = EquivalenceProperties::new(schema);
+        PlanProperties::new(
+            eq_properties,
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        )
+    }
+}
+
+impl DisplayAs for QueryHistoryTableExecutionPlan {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "QueryHistoryTableExecutionPlan")
+    }
+}
+
+impl ExecutionPlan for QueryHistoryTableExecutionPlan {
+    fn name(&self) -> &str {
+        "QueryHistoryTableExecutionPlan"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        _partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema();
+
+        let history_columns = Self::history_columns(&self.query_history.records());
+
+        let mut columns = Vec::<ArrayRef>::new();
+        for field in schema.fields.iter() {
+            let Some(column) = history_columns.get(field.name().as_str()) else {
+                return exec_err!("unknown column found. column: {}", field.name());
+            };
+            columns.push(column.clone());
+        }
+
+        let Ok(rec) = RecordBatch::try_new(schema.clone(), columns) else {
+            return exec_err!("failed to create RecordBatch. schema: {:?}", self.schema());
+        };
+
+        let stream = Box::pin(MemoryStream::try_new(vec![rec], self.schema(), None)?);
+        Ok(stream)
+    }
+}
+
+impl QueryHistoryTableExecutionPlan {
+    fn history_columns(records: &[QueryHistoryRecord]) -> HashMap<&'static str, ArrayRef> {
+        let query_ids: Vec<_> = records.iter().map(|r| r.query_id.clone()).collect();
+        let sources: Vec<_> = records.iter().map(|r| r.source.to_string()).collect();
+        let sqls: Vec<_> = records.iter().map(|r| r.sql.clone()).collect();
+        let started_ats: Vec<_> = records
+            .iter()
+            .map(|r| r.started_at.timestamp_millis())
+            .collect();
+        let durations: Vec<_> = records.iter().map(|r| r.duration_ms).collect();
+        let row_counts: Vec<_> = records.iter().map(|r| r.row_count).collect();
+        let statuses: Vec<_> = records.iter().map(|r| r.status.as_str()).collect();
+        let error_messages: Vec<_> = records.iter().map(|r| r.error_message.clone()).collect();
+
+        HashMap::<&str, ArrayRef>::from([
+            (
+                "query_id",
+                Arc::new(StringArray::from(query_ids)) as ArrayRef,
+            ),
+            ("source", Arc::new(StringArray::from(sources))),
+            ("sql", Arc::new(StringArray::from(sqls))),
+            (
+                "started_at",
+                Arc::new(TimestampMillisecondArray::from(started_ats).with_timezone("UTC")),
+            ),
+            ("duration_ms", Arc::new(Int64Array::from(durations))),
+            ("row_count", Arc::new(Int64Array::from(row_counts))),
+            ("status", Arc::new(StringArray::from(statuses))),
+            ("error_message", Arc::new(StringArray::from(error_messages))),
+        ])
+    }
+}
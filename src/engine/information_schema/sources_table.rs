@@ -0,0 +1,36 @@
+use crate::config::source_config::SourceConfig;
+use datafusion::arrow::array::{ArrayRef, RecordBatch, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::catalog::TableProvider;
+use datafusion::datasource::MemTable;
+use std::sync::Arc;
+
+pub(super) const SOURCES_TABLE_NAME: &str = "sources";
+
+/// Lists the `[[sources]]` in the config. They don't change while running.
+pub(super) fn create_sources_table(sources: &[SourceConfig]) -> Arc<dyn TableProvider> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("name", DataType::Utf8, true),
+        Field::new("format", DataType::Utf8, true),
+        Field::new("path", DataType::Utf8, true),
+        Field::new("description", DataType::Utf8, true),
+    ]));
+
+    let names: Vec<_> = sources.iter().map(|s| s.name.clone()).collect();
+    let formats: Vec<_> = sources.iter().map(|s| s.format.to_lowercase()).collect();
+    let paths: Vec<_> = sources.iter().map(|s| s.path.clone()).collect();
+    let descriptions: Vec<_> = sources.iter().map(|s| s.description.clone()).collect();
+
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(names)) as ArrayRef,
+            Arc::new(StringArray::from(formats)),
+            Arc::new(StringArray::from(paths)),
+            Arc::new(StringArray::from(descriptions)),
+        ],
+    )
+    .unwrap();
+
+    Arc::new(MemTable::try_new(schema, vec![vec![batch]]).unwrap())
+}
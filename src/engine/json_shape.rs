@@ -0,0 +1,134 @@
+use datafusion::common::{Result, exec_err, plan_datafusion_err};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// Reshapes JSON rows before they become a table.
+///
+/// * `flatten` expands nested objects into `user_login`-style columns up to the depth.
+///   `true` expands all levels.
+/// * `explode` turns the list column into a row per element. Rows with an empty list are dropped.
+///   It's applied before `flatten`, so exploded objects are flattened too.
+#[derive(Debug, Clone, Default)]
+pub struct JsonShape {
+    flatten_depth: usize,
+    explode: Option<String>,
+}
+
+impl JsonShape {
+    /// Takes `flatten` and `explode` out of the function options.
+    pub fn take_from(options: &mut HashMap<String, String>) -> Result<Self> {
+        let flatten_depth = match options.remove("flatten").as_deref() {
+            None | Some("false") => 0,
+            Some("true") => usize::MAX,
+            Some(depth) => depth.parse().map_err(|_| {
+                plan_datafusion_err!("flatten must be a depth or true/false: {}", depth)
+            })?,
+        };
+
+        Ok(Self {
+            flatten_depth,
+            explode: options.remove("explode"),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flatten_depth == 0 && self.explode.is_none()
+    }
+
+    pub fn apply(&self, rows: Vec<Value>) -> Result<Vec<Value>> {
+        let rows = match &self.explode {
+            Some(column) => explode(rows, column)?,
+            None => rows,
+        };
+        if self.flatten_depth == 0 {
+            return Ok(rows);
+        }
+
+        Ok(rows
+            .into_iter()
+            .map(|row| match row {
+                Value::Object(object) => {
+                    let mut flattened = Map::new();
+                    flatten_object("", object, self.flatten_depth, &mut flattened);
+                    Value::Object(flattened)
+                }
+                row => row,
+            })
+            .collect())
+    }
+}
+
+/// Same as the options. e.g. `flatten=2, explode=labels`
+impl Display for JsonShape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut options = Vec::new();
+        match self.flatten_depth {
+            0 => {}
+            usize::MAX => options.push("flatten=true".to_string()),
+            depth => options.push(format!("flatten={}", depth)),
+        }
+        if let Some(column) = &self.explode {
+            options.push(format!("explode={}", column));
+        }
+        write!(f, "{}", options.join(", "))
+    }
+}
+
+fn explode(rows: Vec<Value>, column: &str) -> Result<Vec<Value>> {
+    let mut found = false;
+    let mut exploded = Vec::with_capacity(rows.len());
+    for row in rows {
+        let Value::Object(mut object) = row else {
+            exploded.push(row);
+            continue;
+        };
+
+        match object.remove(column) {
+            Some(Value::Array(items)) => {
+                found = true;
+                for item in items {
+                    let mut object = object.clone();
+                    object.insert(column.to_string(), item);
+                    exploded.push(Value::Object(object));
+                }
+            }
+            Some(value) => {
+                found = true;
+                object.insert(column.to_string(), value);
+                exploded.push(Value::Object(object));
+            }
+            None => exploded.push(Value::Object(object)),
+        }
+    }
+
+    if !found && !exploded.is_empty() {
+        return exec_err!("column to explode is not found: {}", column);
+    }
+    Ok(exploded)
+}
+
+fn flatten_object(
+    prefix: &str,
+    object: Map<String, Value>,
+    depth: usize,
+    out: &mut Map<String, Value>,
+) {
+    for (key, value) in object {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{}_{}", prefix, key)
+        };
+
+        match value {
+            Value::Object(inner) if depth > 0 && inner.is_empty() => {
+                out.insert(key, Value::Null);
+            }
+            Value::Object(inner) if depth > 0 => flatten_object(&key, inner, depth - 1, out),
+            value => {
+                out.insert(key, value);
+            }
+        }
+    }
+}
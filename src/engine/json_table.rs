@@ -0,0 +1,226 @@
+use crate::engine::file_listing::{display_location, list_files, read_bytes, read_head};
+use crate::engine::json_shape::JsonShape;
+use datafusion::arrow::array::{ArrayRef, RecordBatch};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::json::reader::infer_json_schema;
+use datafusion::arrow::json::{ArrayWriter, ReaderBuilder};
+use datafusion::catalog::{MemTable, TableProvider};
+use datafusion::common::{Result, exec_datafusion_err, exec_err};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::execution::SessionState;
+use rmcp::model::JsonObject;
+use serde_json::Value;
+use std::io::Cursor;
+use std::sync::Arc;
+
+const DOCUMENT_HEAD_SIZE: u64 = 4096;
+
+/// Creates a table from JSON objects such as MCP responses and rows of JSON documents.
+/// An empty document is a table without columns and rows.
+pub fn create_table_from_values(values: &Vec<Value>) -> Result<Arc<dyn TableProvider>> {
+    let schema = infer_schema(values)?;
+
+    let mut decoder = ReaderBuilder::new(schema.clone()).build_decoder()?;
+    decoder.serialize(values)?;
+    let batches = decoder.flush()?.into_iter().collect();
+
+    let mem = MemTable::try_new(schema, vec![batches])?;
+    Ok(Arc::new(mem))
+}
+
+/// Decodes the JSON objects into the schema. Objects and arrays in string columns are kept as JSON
+/// text, and the fields not in the schema are ignored.
+pub fn values_to_batch(values: &[Value], schema: SchemaRef) -> Result<RecordBatch> {
+    let text_columns: Vec<&str> = schema
+        .fields()
+        .iter()
+        .filter(|f| f.data_type() == &DataType::Utf8)
+        .map(|f| f.name().as_str())
+        .collect();
+    let values: Vec<Value> = values
+        .iter()
+        .map(|value| match value {
+            Value::Object(object) if !text_columns.is_empty() => {
+                let mut object = object.clone();
+                for column in text_columns.iter() {
+                    if let Some(v @ (Value::Object(_) | Value::Array(_))) = object.get(*column) {
+                        let text = v.to_string();
+                        object.insert(column.to_string(), Value::String(text));
+                    }
+                }
+                Value::Object(object)
+            }
+            value => value.clone(),
+        })
+        .collect();
+
+    let mut decoder = ReaderBuilder::new(schema.clone())
+        .with_coerce_primitive(true)
+        .build_decoder()?;
+    decoder.serialize(&values)?;
+    Ok(decoder
+        .flush()?
+        .unwrap_or_else(|| RecordBatch::new_empty(schema)))
+}
+
+/// Converts the values of the array to JSON, None for NULL.
+pub fn array_to_values(array: &ArrayRef) -> Result<Vec<Option<Value>>> {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "value",
+        array.data_type().clone(),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema, vec![array.clone()])?;
+    if batch.num_rows() == 0 {
+        return Ok(vec![]);
+    }
+
+    let mut writer = ArrayWriter::new(Vec::new());
+    writer.write_batches(&[&batch])?;
+    writer.finish()?;
+
+    let rows: Vec<JsonObject> = serde_json::from_slice(&writer.into_inner())
+        .map_err(|e| exec_datafusion_err!("failed to convert values to JSON. {}", e))?;
+    Ok(rows
+        .into_iter()
+        .map(|mut row| row.remove("value"))
+        .collect())
+}
+
+/// Columns for the properties of the JSON Schema. The type is None for objects and arrays.
+pub fn schema_properties(json_schema: &JsonObject) -> Vec<(String, Option<DataType>)> {
+    let Some(Value::Object(properties)) = json_schema.get("properties") else {
+        return vec![];
+    };
+
+    properties
+        .iter()
+        .map(|(name, property)| {
+            let json_type = match property.get("type") {
+                Some(Value::String(t)) => Some(t.as_str()),
+                // e.g. ["string", "null"]
+                Some(Value::Array(types)) => types
+                    .iter()
+                    .filter_map(|t| t.as_str())
+                    .find(|t| *t != "null"),
+                _ => None,
+            };
+            let data_type = match json_type {
+                Some("string") => Some(DataType::Utf8),
+                Some("integer") => Some(DataType::Int64),
+                Some("number") => Some(DataType::Float64),
+                Some("boolean") => Some(DataType::Boolean),
+                _ => None,
+            };
+            (name.clone(), data_type)
+        })
+        .collect()
+}
+
+pub fn infer_schema(values: &[Value]) -> Result<SchemaRef> {
+    let mut schemas = Vec::<Schema>::new();
+    for val in values.iter().take(10) {
+        // TODO: create a way to infer values as is. dumping to json might be slow.
+        let text = serde_json::to_string(val).unwrap();
+        let cursor = Cursor::new(text.clone());
+        let (schema, _) = infer_json_schema(cursor, Some(100))?;
+
+        schemas.push(schema)
+    }
+    let schema = Schema::try_merge(schemas)?;
+
+    Ok(Arc::new(schema))
+}
+
+/// Whether the file is a JSON document (an array or a pretty-printed object) rather than
+/// newline-delimited JSON, judging from its head.
+pub fn is_document(head: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start();
+    match text.chars().next() {
+        Some('[') => true,
+        Some('{') => {
+            let first_line = text.lines().next().unwrap_or_default();
+            serde_json::from_str::<Value>(first_line).is_err()
+        }
+        _ => false,
+    }
+}
+
+/// Returns the rows of the document. `array_path` selects a nested array. e.g. "data.items"
+pub fn document_rows(document: Value, array_path: Option<&str>) -> Result<Vec<Value>> {
+    let mut value = document;
+    if let Some(array_path) = array_path {
+        for key in array_path.split('.').filter(|k| !k.is_empty()) {
+            value = match value {
+                Value::Object(mut map) => map.remove(key),
+                Value::Array(mut vec) => key
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|i| *i < vec.len())
+                    .map(|i| vec.swap_remove(i)),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                exec_datafusion_err!(
+                    "array path not found in the JSON document. path: {}, key: {}",
+                    array_path,
+                    key
+                )
+            })?;
+        }
+    }
+
+    match value {
+        Value::Array(vec) => Ok(vec),
+        Value::Object(_) => Ok(vec![value]),
+        _ => exec_err!(
+            "JSON document is not an array or object. path: {}",
+            array_path.unwrap_or_default()
+        ),
+    }
+}
+
+/// Reads the JSON documents of the url as a table, reshaping the rows.
+/// Returns None for newline-delimited JSON unless `array_path` or the shape is given, which reads
+/// each line as a row.
+pub async fn read_json_documents(
+    state: &SessionState,
+    table_url: &ListingTableUrl,
+    array_path: Option<&str>,
+    shape: &JsonShape,
+) -> Result<Option<Arc<dyn TableProvider>>> {
+    let (store, metas) = list_files(state, table_url).await?;
+    let Some(first) = metas.first() else {
+        return Ok(None);
+    };
+    let document = is_document(&read_head(&store, first, DOCUMENT_HEAD_SIZE).await?);
+    if array_path.is_none() && !document && shape.is_empty() {
+        return Ok(None);
+    }
+
+    let mut rows = Vec::new();
+    for meta in metas.iter() {
+        let bytes = read_bytes(&store, meta).await?;
+        let parse_error = |e: serde_json::Error| {
+            exec_datafusion_err!(
+                "failed to parse JSON document({}). {}",
+                display_location(table_url, meta),
+                e
+            )
+        };
+
+        if document || array_path.is_some() {
+            let document: Value = serde_json::from_slice(&bytes).map_err(parse_error)?;
+            rows.extend(document_rows(document, array_path)?);
+        } else {
+            for line in String::from_utf8_lossy(&bytes).lines() {
+                if !line.trim().is_empty() {
+                    rows.push(serde_json::from_str(line).map_err(parse_error)?);
+                }
+            }
+        }
+    }
+
+    create_table_from_values(&shape.apply(rows)?).map(Some)
+}
@@ -0,0 +1,3 @@
+pub mod materialized_view_statement;
+pub mod materialized_view_store;
+mod materialized_view_table;
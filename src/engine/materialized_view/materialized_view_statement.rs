@@ -0,0 +1,219 @@
+use crate::engine::access_policy::AccessPolicy;
+use crate::engine::materialized_view::materialized_view_store::{
+    MaterializedViewMetadata, MaterializedViewStore,
+};
+use datafusion::arrow::array::{
+    ArrayRef, Int64Array, RecordBatch, StringArray, TimestampMillisecondArray,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use datafusion::common::{Result, plan_datafusion_err, plan_err};
+use datafusion::dataframe::DataFrame;
+use datafusion::logical_expr::LogicalPlanBuilder;
+use datafusion::prelude::SessionContext;
+use datafusion::sql::parser::Statement;
+use datafusion::sql::sqlparser::ast::{
+    CreateTableOptions, Expr, ObjectName, ObjectType, SqlOption, Statement as SQLStatement, Value,
+    ValueWithSpan,
+};
+use datafusion::sql::sqlparser::dialect::GenericDialect;
+use datafusion::sql::sqlparser::tokenizer::{Token, Tokenizer};
+use std::sync::Arc;
+
+const MATERIALIZED_VIEW_OPTIONS: &[&str] = &["ttl_secs"];
+
+/// Runs `REFRESH MATERIALIZED VIEW issues`, which the SQL parser doesn't support, if the sql is.
+pub async fn try_refresh_statement(
+    context: &SessionContext,
+    store: &MaterializedViewStore,
+    sql: &str,
+) -> Result<Option<DataFrame>> {
+    let Some(name) = parse_refresh_statement(sql)? else {
+        return Ok(None);
+    };
+    let Some(view) = store.get(&name) else {
+        return plan_err!("materialized view '{}' not found", name);
+    };
+
+    let metadata = view.refresh(&context.state()).await?;
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("row_count", DataType::Int64, true),
+        Field::new(
+            "refreshed_at",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            true,
+        ),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(StringArray::from(vec![metadata.name])) as ArrayRef,
+            Arc::new(Int64Array::from(vec![metadata.row_count])),
+            Arc::new(
+                TimestampMillisecondArray::from(vec![
+                    metadata.refreshed_at.map(|t| t.timestamp_millis()),
+                ])
+                .with_timezone("UTC"),
+            ),
+        ],
+    )?;
+
+    Ok(Some(context.read_batch(batch)?))
+}
+
+/// Runs `CREATE MATERIALIZED VIEW`, and `DROP VIEW` of a materialized view, if the statement is.
+pub async fn try_materialized_view_statement(
+    context: &SessionContext,
+    access_policy: &AccessPolicy,
+    store: &MaterializedViewStore,
+    statement: &Statement,
+) -> Result<Option<DataFrame>> {
+    let Statement::Statement(statement) = statement else {
+        return Ok(None);
+    };
+
+    match statement.as_ref() {
+        SQLStatement::CreateView {
+            or_replace,
+            materialized: true,
+            name,
+            columns,
+            query,
+            options,
+            if_not_exists,
+            temporary,
+            ..
+        } => {
+            access_policy.check_read_only_statement("CREATE MATERIALIZED VIEW")?;
+            if !columns.is_empty() || *temporary {
+                return plan_err!(
+                    "column list and TEMPORARY are not supported for materialized views"
+                );
+            }
+
+            let name = view_name(name)?;
+            if *if_not_exists && store.get(&name).is_some() {
+                return empty_dataframe(context).map(Some);
+            }
+
+            let metadata = MaterializedViewMetadata {
+                name,
+                sql: query.to_string(),
+                ttl_secs: ttl_secs(options)?,
+                configured: false,
+                refreshed_at: None,
+                row_count: None,
+            };
+            store.create(context, metadata, *or_replace).await?;
+        }
+        SQLStatement::Drop {
+            object_type: ObjectType::View | ObjectType::Table,
+            names,
+            ..
+        } => {
+            let [name] = names.as_slice() else {
+                return Ok(None);
+            };
+            let name = view_name(name)?;
+            if store.get(&name).is_none() {
+                return Ok(None);
+            }
+
+            access_policy.check_read_only_statement("DROP VIEW")?;
+            store.drop_view(context, &name)?;
+        }
+        _ => return Ok(None),
+    }
+
+    empty_dataframe(context).map(Some)
+}
+
+fn parse_refresh_statement(sql: &str) -> Result<Option<String>> {
+    let Ok(tokens) = Tokenizer::new(&GenericDialect {}, sql).tokenize() else {
+        return Ok(None);
+    };
+    let mut tokens = tokens
+        .into_iter()
+        .filter(|t| !matches!(t, Token::Whitespace(_) | Token::SemiColon));
+
+    match tokens.next() {
+        Some(Token::Word(word)) if word.value.eq_ignore_ascii_case("refresh") => {}
+        _ => return Ok(None),
+    }
+
+    let rest: Vec<_> = tokens.collect();
+    match rest.as_slice() {
+        [
+            Token::Word(materialized),
+            Token::Word(view),
+            Token::Word(name),
+        ] if materialized.value.eq_ignore_ascii_case("materialized")
+            && view.value.eq_ignore_ascii_case("view") =>
+        {
+            Ok(Some(normalize(&name.value, name.quote_style)))
+        }
+        _ => plan_err!(
+            "REFRESH takes the name of a materialized view. e.g. REFRESH MATERIALIZED VIEW issues"
+        ),
+    }
+}
+
+/// Materialized views are created only in the default schema.
+fn view_name(name: &ObjectName) -> Result<String> {
+    match name.0.as_slice() {
+        [part] => match part.as_ident() {
+            Some(ident) => Ok(normalize(&ident.value, ident.quote_style)),
+            None => plan_err!("invalid materialized view name: {}", name),
+        },
+        _ => plan_err!(
+            "materialized views can't be qualified by a schema. name: {}",
+            name
+        ),
+    }
+}
+
+/// Same as the other identifiers, unquoted names are lowercased.
+fn normalize(value: &str, quote_style: Option<char>) -> String {
+    match quote_style {
+        Some(_) => value.to_string(),
+        None => value.to_lowercase(),
+    }
+}
+
+fn ttl_secs(options: &CreateTableOptions) -> Result<Option<u64>> {
+    let CreateTableOptions::With(options) = options else {
+        return Ok(None);
+    };
+
+    let mut ttl_secs = None;
+    for option in options {
+        match option {
+            SqlOption::KeyValue { key, value } if key.value.eq_ignore_ascii_case("ttl_secs") => {
+                let Expr::Value(ValueWithSpan {
+                    value: Value::Number(number, _),
+                    ..
+                }) = value
+                else {
+                    return plan_err!("ttl_secs must be a number of seconds. input: {}", value);
+                };
+                ttl_secs = Some(number.parse().map_err(|_| {
+                    plan_datafusion_err!("ttl_secs must be a number of seconds. input: {}", number)
+                })?);
+            }
+            option => {
+                return plan_err!(
+                    "unknown option for materialized view. Option: {}, Supported: [{}]",
+                    option,
+                    MATERIALIZED_VIEW_OPTIONS.join(", ")
+                );
+            }
+        }
+    }
+
+    Ok(ttl_secs)
+}
+
+fn empty_dataframe(context: &SessionContext) -> Result<DataFrame> {
+    let plan = LogicalPlanBuilder::empty(false).build()?;
+    Ok(DataFrame::new(context.state(), plan))
+}
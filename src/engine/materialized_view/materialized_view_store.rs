@@ -0,0 +1,322 @@
+use crate::config::cache_config::CacheConfig;
+use crate::config::materialized_view_config::MaterializedViewConfig;
+use crate::engine::access_policy::AccessPolicy;
+use crate::engine::context::Context;
+use crate::engine::materialized_view::materialized_view_table::MaterializedViewTable;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::common::{Result, TableReference, exec_datafusion_err, plan_err};
+use datafusion::dataframe::DataFrame;
+use datafusion::execution::SessionState;
+use datafusion::parquet::arrow::ArrowWriter;
+use datafusion::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use datafusion::prelude::SessionContext;
+use futures::StreamExt;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+const METADATA_EXTENSION: &str = "json";
+const SNAPSHOT_EXTENSION: &str = "parquet";
+
+/// Stored next to the snapshot as `<name>.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterializedViewMetadata {
+    pub name: String,
+    pub sql: String,
+    pub ttl_secs: Option<u64>,
+    /// Defined by `[[materialized_views]]`. Ignored once removed from the config.
+    #[serde(default)]
+    pub configured: bool,
+    pub refreshed_at: Option<DateTime<Utc>>,
+    pub row_count: Option<i64>,
+}
+
+impl MaterializedViewMetadata {
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        let ttl_secs = i64::try_from(self.ttl_secs?).ok()?;
+        self.refreshed_at?
+            .checked_add_signed(chrono::Duration::seconds(ttl_secs))
+    }
+
+    fn is_stale(&self) -> bool {
+        match (self.refreshed_at, self.expires_at()) {
+            (None, _) => true,
+            (Some(_), Some(expires_at)) => expires_at <= Utc::now(),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MaterializedView {
+    metadata: RwLock<MaterializedViewMetadata>,
+    schema: RwLock<SchemaRef>,
+    metadata_file: PathBuf,
+    snapshot_file: PathBuf,
+    access_policy: Arc<AccessPolicy>,
+    refresh_lock: tokio::sync::Mutex<()>,
+}
+
+impl MaterializedView {
+    pub fn metadata(&self) -> MaterializedViewMetadata {
+        self.metadata.read().unwrap().clone()
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.read().unwrap().clone()
+    }
+
+    pub fn snapshot_file(&self) -> &Path {
+        &self.snapshot_file
+    }
+
+    /// Refreshes the snapshot when the TTL has passed. Another caller refreshing it is waited for.
+    pub async fn refresh_if_stale(&self, state: &SessionState) -> Result<()> {
+        if !self.metadata().is_stale() {
+            return Ok(());
+        }
+
+        let _guard = self.refresh_lock.lock().await;
+        if !self.metadata().is_stale() {
+            return Ok(());
+        }
+        self.write_snapshot(state).await
+    }
+
+    pub async fn refresh(&self, state: &SessionState) -> Result<MaterializedViewMetadata> {
+        let _guard = self.refresh_lock.lock().await;
+        self.write_snapshot(state).await?;
+        Ok(self.metadata())
+    }
+
+    /// Runs the query and replaces the snapshot. The previous one is kept when it fails.
+    async fn write_snapshot(&self, state: &SessionState) -> Result<()> {
+        let sql = self.metadata().sql;
+        let plan = Context::create_logical_plan(state, &sql).await?;
+        self.access_policy.check_plan(&plan)?;
+
+        let df = DataFrame::new(state.clone(), plan);
+        let schema = Arc::new(Schema::from(df.schema()));
+        let mut stream = df.execute_stream().await?;
+
+        let temporary_file = self.snapshot_file.with_extension("parquet.tmp");
+        let mut writer =
+            ArrowWriter::try_new(File::create(&temporary_file)?, schema.clone(), None)?;
+        let mut row_count = 0;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            row_count += batch.num_rows() as i64;
+            writer.write(&batch)?;
+        }
+        writer.close()?;
+        fs::rename(&temporary_file, &self.snapshot_file)?;
+
+        let metadata = {
+            let mut metadata = self.metadata.write().unwrap();
+            metadata.refreshed_at = Some(Utc::now());
+            metadata.row_count = Some(row_count);
+            metadata.clone()
+        };
+        *self.schema.write().unwrap() = schema;
+        write_metadata(&self.metadata_file, &metadata)
+    }
+}
+
+/// Keeps materialized views, whose snapshots are Parquet files in the cache directory. They are
+/// restored at the start from the metadata files there, together with `[[materialized_views]]`.
+#[derive(Debug)]
+pub struct MaterializedViewStore {
+    dir: PathBuf,
+    views: DashMap<String, Arc<MaterializedView>>,
+    access_policy: Arc<AccessPolicy>,
+}
+
+impl MaterializedViewStore {
+    pub fn new(config: &CacheConfig, access_policy: Arc<AccessPolicy>) -> Self {
+        Self {
+            dir: config.cache_dir(),
+            views: DashMap::new(),
+            access_policy,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<MaterializedView>> {
+        self.views.get(name).map(|view| view.clone())
+    }
+
+    pub fn list(&self) -> Vec<Arc<MaterializedView>> {
+        let mut views: Vec<_> = self.views.iter().map(|view| view.clone()).collect();
+        views.sort_by_key(|view| view.metadata().name);
+        views
+    }
+
+    /// Registers the stored views and the configured ones. A view without a snapshot yet is
+    /// refreshed here, since the schema is needed to register it.
+    pub async fn restore(&self, context: &SessionContext, configs: &[MaterializedViewConfig]) {
+        let mut definitions: Vec<MaterializedViewMetadata> = self
+            .load_metadata()
+            .into_iter()
+            .filter(|m| !m.configured)
+            .filter(|m| !configs.iter().any(|c| c.name == m.name))
+            .collect();
+
+        for config in configs {
+            let stored = self.read_metadata(&config.name);
+            let snapshot = stored
+                .filter(|m| m.sql == config.sql)
+                .map(|m| (m.refreshed_at, m.row_count))
+                .unwrap_or_default();
+            definitions.push(MaterializedViewMetadata {
+                name: config.name.clone(),
+                sql: config.sql.clone(),
+                ttl_secs: config.ttl_secs,
+                configured: true,
+                refreshed_at: snapshot.0,
+                row_count: snapshot.1,
+            });
+        }
+
+        for metadata in definitions {
+            let name = metadata.name.clone();
+            if let Err(e) = self.register(context, metadata).await {
+                warn!("failed to restore materialized view({}). {}", name, e);
+            }
+        }
+    }
+
+    /// Creates the view, taking the first snapshot.
+    pub async fn create(
+        &self,
+        context: &SessionContext,
+        metadata: MaterializedViewMetadata,
+        or_replace: bool,
+    ) -> Result<Arc<MaterializedView>> {
+        let exists = context.table_exist(TableReference::bare(metadata.name.clone()))?;
+        if exists && !(or_replace && self.views.contains_key(&metadata.name)) {
+            return plan_err!("table '{}' already exists", metadata.name);
+        }
+
+        self.register(context, metadata).await
+    }
+
+    /// Deregisters the view and removes its files. Returns false if it isn't a materialized view.
+    pub fn drop_view(&self, context: &SessionContext, name: &str) -> Result<bool> {
+        let Some((_, view)) = self.views.remove(name) else {
+            return Ok(false);
+        };
+
+        context.deregister_table(TableReference::bare(name))?;
+        for file in [&view.metadata_file, &view.snapshot_file] {
+            if let Err(e) = fs::remove_file(file)
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                warn!("failed to remove {}. {}", file.display(), e);
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn register(
+        &self,
+        context: &SessionContext,
+        metadata: MaterializedViewMetadata,
+    ) -> Result<Arc<MaterializedView>> {
+        fs::create_dir_all(&self.dir).map_err(|e| {
+            exec_datafusion_err!(
+                "failed to create cache directory({}). {}",
+                self.dir.display(),
+                e
+            )
+        })?;
+
+        let stem = file_stem(&metadata.name);
+        let snapshot_file = self.dir.join(format!("{}.{}", stem, SNAPSHOT_EXTENSION));
+        let schema = match metadata.refreshed_at {
+            Some(_) => read_snapshot_schema(&snapshot_file).ok(),
+            None => None,
+        };
+        let has_snapshot = schema.is_some();
+
+        let view = Arc::new(MaterializedView {
+            metadata: RwLock::new(metadata.clone()),
+            schema: RwLock::new(schema.unwrap_or_else(|| Arc::new(Schema::empty()))),
+            metadata_file: self.dir.join(format!("{}.{}", stem, METADATA_EXTENSION)),
+            snapshot_file,
+            access_policy: self.access_policy.clone(),
+            refresh_lock: tokio::sync::Mutex::new(()),
+        });
+        if !has_snapshot {
+            view.refresh(&context.state()).await?;
+        }
+
+        let table = MaterializedViewTable::new(view.clone());
+        if self.views.remove(&metadata.name).is_some() {
+            context.deregister_table(TableReference::bare(metadata.name.clone()))?;
+        }
+        context.register_table(TableReference::bare(metadata.name.clone()), Arc::new(table))?;
+        self.views.insert(metadata.name, view.clone());
+
+        Ok(view)
+    }
+
+    fn load_metadata(&self) -> Vec<MaterializedViewMetadata> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return vec![];
+        };
+
+        let mut metadata: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == METADATA_EXTENSION))
+            .filter_map(|path| read_metadata_file(&path))
+            .collect();
+        metadata.sort_by(|a, b| a.name.cmp(&b.name));
+        metadata
+    }
+
+    fn read_metadata(&self, name: &str) -> Option<MaterializedViewMetadata> {
+        let path = self
+            .dir
+            .join(format!("{}.{}", file_stem(name), METADATA_EXTENSION));
+        read_metadata_file(&path)
+    }
+}
+
+fn read_metadata_file(path: &Path) -> Option<MaterializedViewMetadata> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content)
+        .inspect_err(|e| warn!("failed to parse {}. {}", path.display(), e))
+        .ok()
+}
+
+fn write_metadata(path: &Path, metadata: &MaterializedViewMetadata) -> Result<()> {
+    let content = serde_json::to_string_pretty(metadata)
+        .map_err(|e| exec_datafusion_err!("failed to serialize metadata. {}", e))?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn read_snapshot_schema(path: &Path) -> Result<SchemaRef> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?;
+    Ok(builder.schema().clone())
+}
+
+/// Keeps the name readable in the file name, escaping the characters not safe for file systems.
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' => c.to_string(),
+            c => c
+                .to_string()
+                .bytes()
+                .map(|b| format!("%{:02X}", b))
+                .collect(),
+        })
+        .collect()
+}
@@ -0,0 +1,81 @@
+use crate::engine::materialized_view::materialized_view_store::MaterializedView;
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::catalog::{Session, TableProvider};
+use datafusion::common::{Result, exec_err, plan_err};
+use datafusion::datasource::TableType;
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::listing::{
+    ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
+};
+use datafusion::execution::SessionState;
+use datafusion::logical_expr::{Expr, TableProviderFilterPushDown};
+use datafusion::physical_plan::ExecutionPlan;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Reads the snapshot of a materialized view, refreshing it first when the TTL has passed.
+#[derive(Debug)]
+pub(super) struct MaterializedViewTable {
+    view: Arc<MaterializedView>,
+}
+
+impl MaterializedViewTable {
+    pub(super) fn new(view: Arc<MaterializedView>) -> Self {
+        Self { view }
+    }
+}
+
+#[async_trait]
+impl TableProvider for MaterializedViewTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.view.schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> Result<Vec<TableProviderFilterPushDown>> {
+        Ok(vec![TableProviderFilterPushDown::Inexact; filters.len()])
+    }
+
+    async fn scan(
+        &self,
+        state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let Some(session_state) = state.as_any().downcast_ref::<SessionState>() else {
+            return exec_err!("failed to refresh the materialized view. unknown session");
+        };
+        let planned_schema = self.view.schema();
+        self.view.refresh_if_stale(session_state).await?;
+
+        let schema = self.view.schema();
+        if schema != planned_schema {
+            return plan_err!(
+                "the columns of materialized view {} changed by the refresh. Run the query again",
+                self.view.metadata().name
+            );
+        }
+
+        let table_url = ListingTableUrl::parse(self.view.snapshot_file().to_string_lossy())?;
+        let options =
+            ListingOptions::new(Arc::new(ParquetFormat::default())).with_file_extension(".parquet");
+        let config = ListingTableConfig::new(table_url)
+            .with_listing_options(options)
+            .with_schema(schema);
+        let table = ListingTable::try_new(config)?;
+
+        table.scan(state, projection, filters, limit).await
+    }
+}
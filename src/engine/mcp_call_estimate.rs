@@ -0,0 +1,21 @@
+use crate::engine::exec_mcp_execution_plan::ExecMcpExecutionPlan;
+use crate::engine::mcp_call_table::McpCallExecutionPlan;
+use datafusion::common::tree_node::{TreeNode, TreeNodeRecursion};
+use datafusion::physical_plan::ExecutionPlan;
+use std::sync::Arc;
+
+/// Best-effort count of the MCP calls of the plan, from the row statistics of the inputs. Calls
+/// over inputs without the statistics, such as filters of unknown selectivity, are left out.
+pub fn estimate_mcp_calls(plan: &Arc<dyn ExecutionPlan>) -> usize {
+    let mut calls = 0;
+    let _ = plan.apply(|plan| {
+        if let Some(exec_mcp) = plan.as_any().downcast_ref::<ExecMcpExecutionPlan>() {
+            calls += exec_mcp.estimate_calls().unwrap_or_default();
+        } else if plan.as_any().is::<McpCallExecutionPlan>() {
+            calls += 1;
+        }
+        Ok(TreeNodeRecursion::Continue)
+    });
+
+    calls
+}
@@ -0,0 +1,106 @@
+use crate::config::mcp_call_log_config::McpCallLogConfig;
+use crate::util::path::expand_home;
+use crate::util::redact::redact_json;
+use chrono::{DateTime, Utc};
+use log::warn;
+use rmcp::model::JsonObject;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct McpCallRecord {
+    pub called_at: DateTime<Utc>,
+    pub query_id: Option<String>,
+    pub server_name: String,
+    pub tool_name: String,
+    pub arguments: Option<String>,
+    pub latency_ms: i64,
+    pub response_bytes: Option<i64>,
+    pub cache_hit: bool,
+    pub error: Option<String>,
+}
+
+/// Keeps the latest calls made to MCP servers.
+#[derive(Debug)]
+pub struct McpCallLog {
+    records: Mutex<VecDeque<McpCallRecord>>,
+    max_records: usize,
+    redact_arguments: bool,
+    audit_file: Option<Mutex<File>>,
+}
+
+impl McpCallLog {
+    pub fn new(config: &McpCallLogConfig) -> Self {
+        let audit_file = config
+            .audit_file
+            .as_deref()
+            .map(expand_home)
+            .and_then(
+                |path| match OpenOptions::new().create(true).append(true).open(&path) {
+                    Ok(file) => Some(Mutex::new(file)),
+                    Err(e) => {
+                        warn!(
+                            "failed to open mcp call audit file({}). {}",
+                            path.display(),
+                            e
+                        );
+                        None
+                    }
+                },
+            );
+
+        Self {
+            records: Mutex::new(VecDeque::with_capacity(config.max_records)),
+            max_records: config.max_records,
+            redact_arguments: config.redact_arguments,
+            audit_file,
+        }
+    }
+
+    pub fn format_arguments(&self, arguments: &Option<JsonObject>) -> Option<String> {
+        let arguments = arguments.as_ref()?;
+        let redacted = redact_json(&Value::Object(arguments.clone()), self.redact_arguments);
+
+        serde_json::to_string(&redacted).ok()
+    }
+
+    pub fn record(&self, record: McpCallRecord) {
+        self.append_to_audit_file(&record);
+
+        let mut records = self.records.lock().unwrap();
+        if self.max_records == 0 {
+            return;
+        }
+        while records.len() >= self.max_records {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    pub fn records(&self) -> Vec<McpCallRecord> {
+        self.records.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn append_to_audit_file(&self, record: &McpCallRecord) {
+        let Some(file) = &self.audit_file else {
+            return;
+        };
+
+        let line = match serde_json::to_string(record) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("failed to serialize mcp call record. {}", e);
+                return;
+            }
+        };
+
+        let mut file = file.lock().unwrap();
+        if let Err(e) = writeln!(file, "{}", line) {
+            warn!("failed to write mcp call audit file. {}", e);
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use crate::config::mcp_server_config::McpServerConfig;
+use crate::engine::json_shape::JsonShape;
+use crate::engine::json_table::{infer_schema, values_to_batch};
+use crate::engine::mcp_runtime::McpRuntime;
+use crate::engine::mcp_tool_caller::McpToolCaller;
+use crate::engine::query_state::{QueryState, QueryStateExtension};
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::catalog::{Session, TableProvider};
+use datafusion::common::{Result, internal_err};
+use datafusion::datasource::TableType;
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::Expr;
+use datafusion::physical_expr::{EquivalenceProperties, Partitioning};
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use rmcp::model::JsonObject;
+use serde_json::Value;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+/// Where the columns of the call come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum McpCallSchemaSource {
+    /// `schema => 'id BIGINT, title VARCHAR'`
+    Declared,
+    /// The output schema of the tool.
+    OutputSchema,
+    /// The response of the tool, called while planning or by a previous query.
+    Probed,
+    /// None of the above, left empty since the statement is only planned, such as `EXPLAIN`.
+    Unknown,
+}
+
+impl Display for McpCallSchemaSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            McpCallSchemaSource::Declared => write!(f, "declared"),
+            McpCallSchemaSource::OutputSchema => write!(f, "output_schema"),
+            McpCallSchemaSource::Probed => write!(f, "probed"),
+            McpCallSchemaSource::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// A call of call_mcp, made when the plan is executed.
+#[derive(Debug, Clone)]
+pub struct McpCall {
+    pub server_config: Arc<McpServerConfig>,
+    pub tool_name: String,
+    pub arguments: Option<JsonObject>,
+    pub shape: JsonShape,
+}
+
+impl McpCall {
+    /// Identifies the call in the caches of the runtime.
+    pub fn key(&self) -> String {
+        format!(
+            "{}\n{}\n{}\n{}",
+            self.server_config.name,
+            self.tool_name,
+            serde_json::to_string(&self.arguments).unwrap_or_default(),
+            self.shape
+        )
+    }
+
+    /// Calls the tool, unless it was called while planning the statement.
+    pub async fn values(
+        &self,
+        runtime: &Arc<McpRuntime>,
+        query_state: &Arc<QueryState>,
+    ) -> Result<Vec<Value>> {
+        let tool_caller = McpToolCaller::new(
+            self.server_config.clone(),
+            runtime.clone(),
+            query_state.clone(),
+        );
+        if let Some(values) = query_state.take_planned_response(&self.key()) {
+            tool_caller.record_cache_hit(self.tool_name.clone(), &self.arguments, &values);
+            return Ok(values);
+        }
+
+        tool_caller
+            .call_as_values(self.tool_name.clone(), self.arguments.clone())
+            .await
+    }
+}
+
+/// The result of call_mcp. The tool isn't called until the plan is executed.
+#[derive(Debug)]
+pub struct McpCallTable {
+    call: McpCall,
+    schema: SchemaRef,
+    schema_source: McpCallSchemaSource,
+    runtime: Arc<McpRuntime>,
+}
+
+impl McpCallTable {
+    pub fn new(
+        call: McpCall,
+        schema: SchemaRef,
+        schema_source: McpCallSchemaSource,
+        runtime: Arc<McpRuntime>,
+    ) -> Self {
+        Self {
+            call,
+            schema,
+            schema_source,
+            runtime,
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for McpCallTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(McpCallExecutionPlan::try_new(
+            self.call.clone(),
+            self.schema.clone(),
+            projection.cloned(),
+            self.schema_source,
+            self.runtime.clone(),
+        )?))
+    }
+}
+
+#[derive(Debug)]
+pub struct McpCallExecutionPlan {
+    call: McpCall,
+    table_schema: SchemaRef,
+    projection: Option<Vec<usize>>,
+    schema_source: McpCallSchemaSource,
+    runtime: Arc<McpRuntime>,
+    plan_properties: PlanProperties,
+}
+
+impl McpCallExecutionPlan {
+    fn try_new(
+        call: McpCall,
+        table_schema: SchemaRef,
+        projection: Option<Vec<usize>>,
+        schema_source: McpCallSchemaSource,
+        runtime: Arc<McpRuntime>,
+    ) -> Result<Self> {
+        let schema = match &projection {
+            Some(projection) => Arc::new(table_schema.project(projection)?),
+            None => table_schema.clone(),
+        };
+        let plan_properties = PlanProperties::new(
+            EquivalenceProperties::new(schema),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        );
+
+        Ok(Self {
+            call,
+            table_schema,
+            projection,
+            schema_source,
+            runtime,
+            plan_properties,
+        })
+    }
+}
+
+impl DisplayAs for McpCallExecutionPlan {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "McpCallExecutionPlan: server={}, tool={}",
+            self.call.server_config.name, self.call.tool_name
+        )?;
+        if let Some(arguments) = self.runtime.call_log.format_arguments(&self.call.arguments) {
+            write!(f, ", arguments={}", arguments)?;
+        }
+        if !self.call.shape.is_empty() {
+            write!(f, ", {}", self.call.shape)?;
+        }
+        write!(f, ", schema={}", self.schema_source)
+    }
+}
+
+impl ExecutionPlan for McpCallExecutionPlan {
+    fn name(&self) -> &str {
+        "McpCallExecutionPlan"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !children.is_empty() {
+            return internal_err!("McpCallExecutionPlan takes no children");
+        }
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        _partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let query_state = QueryStateExtension::query_state(context.session_config().options());
+        let call = self.call.clone();
+        let table_schema = self.table_schema.clone();
+        let projection = self.projection.clone();
+        let schema_source = self.schema_source;
+        let runtime = self.runtime.clone();
+
+        let stream = futures::stream::once(async move {
+            let values = call
+                .shape
+                .apply(call.values(&runtime, &query_state).await?)?;
+            // Keeps up with the response, used by the next planning of the same call.
+            if schema_source == McpCallSchemaSource::Probed && !values.is_empty() {
+                runtime
+                    .response_schemas
+                    .insert(call.key(), infer_schema(&values)?);
+            }
+
+            let batch = values_to_batch(&values, table_schema)?;
+            match projection {
+                Some(projection) => Ok(batch.project(&projection)?),
+                None => Ok(batch),
+            }
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+}
@@ -0,0 +1,29 @@
+use crate::config::config::Config;
+use crate::engine::mcp_call_log::McpCallLog;
+use crate::engine::rate_limiter::RateLimiter;
+use crate::engine::tool_policy::ToolPolicy;
+use dashmap::DashMap;
+use datafusion::arrow::datatypes::SchemaRef;
+use std::sync::Arc;
+
+/// State shared by every call to MCP servers.
+#[derive(Debug)]
+pub struct McpRuntime {
+    pub call_log: Arc<McpCallLog>,
+    pub tool_policy: ToolPolicy,
+    pub rate_limiter: RateLimiter,
+    /// Schemas of call_mcp results seen last time, by the call. Planning uses them rather than
+    /// calling the tool.
+    pub response_schemas: DashMap<String, SchemaRef>,
+}
+
+impl McpRuntime {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            call_log: Arc::new(McpCallLog::new(&config.mcp_call_log)),
+            tool_policy: ToolPolicy::new(config.tool_policy.clone()),
+            rate_limiter: RateLimiter::default(),
+            response_schemas: DashMap::new(),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use crate::config::mcp_server_config::McpServerConfig;
+use crate::engine::mcp_runtime::McpRuntime;
+use crate::engine::mcp_tool_caller::McpToolCaller;
+use crate::engine::query_state::QueryStateExtension;
+use async_trait::async_trait;
+use datafusion::arrow::array::{ArrayRef, RecordBatch, StringArray, UInt64Array};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::json::ArrayWriter;
+use datafusion::catalog::{Session, TableProvider};
+use datafusion::common::{DataFusionError, Result, exec_datafusion_err, not_impl_err, plan_err};
+use datafusion::datasource::TableType;
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::Expr;
+use datafusion::logical_expr::dml::InsertOp;
+use datafusion::physical_expr::{Distribution, EquivalenceProperties, Partitioning};
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use futures::StreamExt;
+use rmcp::model::JsonObject;
+use serde_json::Value;
+use std::any::Any;
+use std::collections::HashSet;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+/// Calls the tool once per inserted row, passing the non-null columns as the arguments.
+#[derive(Debug)]
+pub struct McpSinkTable {
+    schema: SchemaRef,
+    server_config: Arc<McpServerConfig>,
+    tool_name: String,
+    /// String columns for object or array arguments, passed as parsed JSON.
+    json_columns: HashSet<String>,
+    runtime: Arc<McpRuntime>,
+}
+
+impl McpSinkTable {
+    pub fn new(
+        schema: SchemaRef,
+        server_config: Arc<McpServerConfig>,
+        tool_name: String,
+        json_columns: HashSet<String>,
+        runtime: Arc<McpRuntime>,
+    ) -> Self {
+        Self {
+            schema,
+            server_config,
+            tool_name,
+            json_columns,
+            runtime,
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for McpSinkTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan_err!(
+            "tool({}) of mcp-server({}) is a sink. It can only be inserted into",
+            self.tool_name,
+            self.server_config.name
+        )
+    }
+
+    async fn insert_into(
+        &self,
+        _state: &dyn Session,
+        input: Arc<dyn ExecutionPlan>,
+        insert_op: InsertOp,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if insert_op != InsertOp::Append {
+            return not_impl_err!("{} is not supported for mcp sinks", insert_op);
+        }
+
+        Ok(Arc::new(McpSinkExecutionPlan::new(
+            input,
+            self.server_config.clone(),
+            self.tool_name.clone(),
+            self.json_columns.clone(),
+            self.runtime.clone(),
+        )))
+    }
+}
+
+/// Returns a row of the numbers of succeeded and failed calls, and the first error.
+#[derive(Debug)]
+struct McpSinkExecutionPlan {
+    input: Arc<dyn ExecutionPlan>,
+    server_config: Arc<McpServerConfig>,
+    tool_name: String,
+    json_columns: HashSet<String>,
+    runtime: Arc<McpRuntime>,
+    plan_properties: PlanProperties,
+}
+
+impl McpSinkExecutionPlan {
+    fn new(
+        input: Arc<dyn ExecutionPlan>,
+        server_config: Arc<McpServerConfig>,
+        tool_name: String,
+        json_columns: HashSet<String>,
+        runtime: Arc<McpRuntime>,
+    ) -> Self {
+        let plan_properties = PlanProperties::new(
+            EquivalenceProperties::new(Self::result_schema()),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        );
+
+        Self {
+            input,
+            server_config,
+            tool_name,
+            json_columns,
+            runtime,
+            plan_properties,
+        }
+    }
+
+    fn result_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("succeeded", DataType::UInt64, false),
+            Field::new("failed", DataType::UInt64, false),
+            Field::new("first_error", DataType::Utf8, true),
+        ]))
+    }
+
+    /// Converts the rows to tool arguments, leaving out null columns.
+    fn to_arguments(
+        batch: &RecordBatch,
+        json_columns: &HashSet<String>,
+    ) -> Result<Vec<JsonObject>> {
+        let mut writer = ArrayWriter::new(Vec::new());
+        writer.write_batches(&[batch])?;
+        writer.finish()?;
+
+        let mut rows: Vec<JsonObject> = serde_json::from_slice(&writer.into_inner())
+            .map_err(|e| exec_datafusion_err!("failed to convert rows to arguments. {}", e))?;
+        for row in rows.iter_mut() {
+            for column in json_columns {
+                if let Some(Value::String(text)) = row.get(column)
+                    && let Ok(value) = serde_json::from_str::<Value>(text)
+                {
+                    row.insert(column.clone(), value);
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+}
+
+impl DisplayAs for McpSinkExecutionPlan {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "McpSinkExecutionPlan: server={}, tool={}",
+            self.server_config.name, self.tool_name
+        )
+    }
+}
+
+impl ExecutionPlan for McpSinkExecutionPlan {
+    fn name(&self) -> &str {
+        "McpSinkExecutionPlan"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let [input] = children
+            .try_into()
+            .map_err(|_| exec_datafusion_err!("McpSinkExecutionPlan takes exactly one input"))?;
+
+        Ok(Arc::new(Self::new(
+            input,
+            self.server_config.clone(),
+            self.tool_name.clone(),
+            self.json_columns.clone(),
+            self.runtime.clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let query_state = QueryStateExtension::query_state(context.session_config().options());
+        let mut input = self.input.execute(partition, context)?;
+        let server_config = self.server_config.clone();
+        let tool_name = self.tool_name.clone();
+        let json_columns = self.json_columns.clone();
+        let runtime = self.runtime.clone();
+
+        let stream = futures::stream::once(async move {
+            // A tool denied by the policy fails the statement rather than every row.
+            runtime
+                .tool_policy
+                .check(&server_config, &tool_name, &query_state)
+                .await?;
+
+            let mut succeeded = 0u64;
+            let mut failed = 0u64;
+            let mut first_error = None;
+            while let Some(batch) = input.next().await {
+                for arguments in Self::to_arguments(&batch?, &json_columns)? {
+                    let result = McpToolCaller::new(
+                        server_config.clone(),
+                        runtime.clone(),
+                        query_state.clone(),
+                    )
+                    .call_checked(tool_name.clone(), Some(arguments))
+                    .await;
+
+                    match result {
+                        Ok(_) => succeeded += 1,
+                        // The rest of the rows would fail the same, so the statement stops here.
+                        Err(e @ DataFusionError::ResourcesExhausted(_)) => {
+                            return Err(e.context(format!(
+                                "stopped sending rows to mcp-server({}) after {} succeeded and {} failed",
+                                server_config.name, succeeded, failed
+                            )));
+                        }
+                        Err(e) => {
+                            failed += 1;
+                            first_error.get_or_insert(e.to_string());
+                        }
+                    }
+                }
+            }
+
+            Ok(RecordBatch::try_new(
+                Self::result_schema(),
+                vec![
+                    Arc::new(UInt64Array::from(vec![succeeded])) as ArrayRef,
+                    Arc::new(UInt64Array::from(vec![failed])),
+                    Arc::new(StringArray::from(vec![first_error])),
+                ],
+            )?)
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            Self::result_schema(),
+            stream,
+        )))
+    }
+}
@@ -0,0 +1,107 @@
+use crate::config::mcp_server_config::McpServerConfig;
+use crate::engine::json_table::schema_properties;
+use crate::engine::mcp_runtime::McpRuntime;
+use crate::engine::mcp_sink_table::McpSinkTable;
+use crate::infra::mcp_client::McpClient;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::catalog::{Session, TableProvider, TableProviderFactory};
+use datafusion::common::{Result, plan_err};
+use datafusion::logical_expr::CreateExternalTable;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+pub const MCP_FILE_TYPE: &str = "MCP";
+
+/// Creates sinks for `CREATE EXTERNAL TABLE messages STORED AS MCP LOCATION 'slack/send_message'`.
+#[derive(Debug)]
+pub(super) struct McpSinkTableFactory {
+    servers: DashMap<String, Arc<McpServerConfig>>,
+    runtime: Arc<McpRuntime>,
+}
+
+impl McpSinkTableFactory {
+    pub(super) fn new(
+        servers: DashMap<String, Arc<McpServerConfig>>,
+        runtime: Arc<McpRuntime>,
+    ) -> Self {
+        Self { servers, runtime }
+    }
+}
+
+#[async_trait]
+impl TableProviderFactory for McpSinkTableFactory {
+    async fn create(
+        &self,
+        _state: &dyn Session,
+        cmd: &CreateExternalTable,
+    ) -> Result<Arc<dyn TableProvider>> {
+        let Some((server_name, tool_name)) = cmd.location.split_once('/') else {
+            return plan_err!(
+                "location of mcp sinks must be 'server/tool'. e.g. 'slack/send_message'. input: {}",
+                cmd.location
+            );
+        };
+        if !cmd.options.is_empty() {
+            return plan_err!("mcp sinks take no options");
+        }
+
+        let Some(server_config) = self.servers.get(server_name).map(|c| c.clone()) else {
+            return plan_err!(
+                "not registered mcp server is used for the sink. Server: {}",
+                server_name
+            );
+        };
+        if !server_config.is_tool_allowed(tool_name) {
+            return plan_err!(
+                "not allowed tool is used for the sink. Server: {}, Tool: {}",
+                server_name,
+                tool_name
+            );
+        }
+
+        let tools = McpClient::new(server_config.clone()).list_tools().await?;
+        let Some(tool) = tools.iter().find(|tool| tool.name == tool_name) else {
+            return plan_err!(
+                "tool({}) is not found in mcp-server({})",
+                tool_name,
+                server_name
+            );
+        };
+
+        let properties = schema_properties(&tool.input_schema);
+        let json_columns: HashSet<String> = properties
+            .iter()
+            .filter(|(_, data_type)| data_type.is_none())
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        let schema = if cmd.schema.fields().is_empty() {
+            if properties.is_empty() {
+                return plan_err!(
+                    "tool({}) of mcp-server({}) has no input properties. Declare the columns",
+                    tool_name,
+                    server_name
+                );
+            }
+            let fields: Vec<_> = properties
+                .into_iter()
+                .map(|(name, data_type)| {
+                    Field::new(name, data_type.unwrap_or(DataType::Utf8), true)
+                })
+                .collect();
+            Arc::new(Schema::new(fields))
+        } else {
+            Arc::new(Schema::from(cmd.schema.as_ref()))
+        };
+
+        Ok(Arc::new(McpSinkTable::new(
+            schema,
+            server_config,
+            tool_name.to_string(),
+            json_columns,
+            self.runtime.clone(),
+        )))
+    }
+}
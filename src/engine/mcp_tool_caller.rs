@@ -1,48 +1,126 @@
 use crate::config::mcp_server_config::McpServerConfig;
+use crate::engine::mcp_call_log::McpCallRecord;
+use crate::engine::mcp_runtime::McpRuntime;
+use crate::engine::query_state::QueryState;
 use crate::infra::mcp_client::McpClient;
-use datafusion::common::{DataFusionError, exec_err};
-use rmcp::model::JsonObject;
+use chrono::Utc;
+use datafusion::common::{exec_err, resources_err};
+use regex::Regex;
 use rmcp::model::RawContent::Text;
+use rmcp::model::{CallToolResult, JsonObject};
 use rmcp::serde_json;
 use serde_json::Value;
-use std::sync::{Arc, mpsc};
-use std::thread;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+/// Retries of a call rejected for rate limiting.
+const MAX_RATE_LIMITED_RETRIES: usize = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+static RATE_LIMITED_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"rate ?limit|too many requests|\b429\b.*(too many|rate)|(too many|rate).*\b429\b")
+        .unwrap()
+});
 
 pub(super) struct McpToolCaller {
     server_config: Arc<McpServerConfig>,
+    runtime: Arc<McpRuntime>,
+    query_state: Arc<QueryState>,
 }
 
 impl McpToolCaller {
-    pub fn new(config: Arc<McpServerConfig>) -> Self {
+    pub fn new(
+        config: Arc<McpServerConfig>,
+        runtime: Arc<McpRuntime>,
+        query_state: Arc<QueryState>,
+    ) -> Self {
         Self {
             server_config: config,
+            runtime,
+            query_state,
         }
     }
 
-    pub fn call_as_string(
+    pub async fn call_as_string(
         &self,
         tool_name: String,
         arguments: Option<JsonObject>,
     ) -> datafusion::common::Result<String> {
-        let cli = McpClient::new(self.server_config.clone());
+        self.call(tool_name, arguments, false).await
+    }
 
-        let (tx, rx) = mpsc::channel();
+    /// Same as `call_as_string` but a response flagged by `isError` is an error too.
+    pub async fn call_checked(
+        &self,
+        tool_name: String,
+        arguments: Option<JsonObject>,
+    ) -> datafusion::common::Result<String> {
+        self.call(tool_name, arguments, true).await
+    }
 
-        // Use thread to make it sync.
-        thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            let result: datafusion::common::Result<_> = rt.block_on(async move {
-                let response = cli.call_tool(tool_name.as_str(), arguments).await?;
+    async fn call(
+        &self,
+        tool_name: String,
+        arguments: Option<JsonObject>,
+        error_result_as_error: bool,
+    ) -> datafusion::common::Result<String> {
+        let called_at = Utc::now();
+        let started_at = Instant::now();
+        let call_log = &self.runtime.call_log;
+        let formatted_arguments = call_log.format_arguments(&arguments);
 
-                return Ok(response);
-            });
+        let result = self
+            .call_tool_text(tool_name.clone(), arguments, error_result_as_error)
+            .await;
 
-            tx.send(result).ok();
+        call_log.record(McpCallRecord {
+            called_at,
+            query_id: self.query_state.query_id(),
+            server_name: self.server_config.name.clone(),
+            tool_name,
+            arguments: formatted_arguments,
+            latency_ms: started_at.elapsed().as_millis() as i64,
+            response_bytes: result.as_ref().ok().map(|text| text.len() as i64),
+            cache_hit: false,
+            error: result.as_ref().err().map(|e| e.to_string()),
         });
 
-        let response = rx
-            .recv()
-            .map_err(|e| DataFusionError::Execution(format!("{:?}", e)))??;
+        result
+    }
+
+    /// Records a response reused from an earlier call of the statement, instead of calling the tool.
+    pub fn record_cache_hit(
+        &self,
+        tool_name: String,
+        arguments: &Option<JsonObject>,
+        values: &[Value],
+    ) {
+        let call_log = &self.runtime.call_log;
+        call_log.record(McpCallRecord {
+            called_at: Utc::now(),
+            query_id: self.query_state.query_id(),
+            server_name: self.server_config.name.clone(),
+            tool_name,
+            arguments: call_log.format_arguments(arguments),
+            latency_ms: 0,
+            response_bytes: serde_json::to_string(values).ok().map(|t| t.len() as i64),
+            cache_hit: true,
+            error: None,
+        });
+    }
+
+    async fn call_tool_text(
+        &self,
+        tool_name: String,
+        arguments: Option<JsonObject>,
+        error_result_as_error: bool,
+    ) -> datafusion::common::Result<String> {
+        self.runtime
+            .tool_policy
+            .check(&self.server_config, tool_name.as_str(), &self.query_state)
+            .await?;
+
+        let response = self.call_tool_with_backoff(&tool_name, arguments).await?;
 
         let response_raw = response.content.first().map(|v| v.clone().raw);
         let Some(Text(response_text)) = response_raw else {
@@ -53,15 +131,130 @@ impl McpToolCaller {
             );
         };
 
+        if error_result_as_error && response.is_error == Some(true) {
+            return exec_err!(
+                "tool({}) of mcp-server({}) returned an error. {}",
+                tool_name,
+                self.server_config.name,
+                response_text.text
+            );
+        }
+
         Ok(response_text.text)
     }
 
-    pub fn call_as_values(
+    /// Fails the statement rather than making more calls than allowed, or calls after it ended.
+    /// Each retry counts as a call.
+    fn count_call(&self) -> datafusion::common::Result<()> {
+        let query_state = &self.query_state;
+        if query_state.is_finished() {
+            return exec_err!(
+                "the statement has already ended, such as by the timeout. No more MCP calls are made"
+            );
+        }
+        let (calls, server_calls) = query_state.add_mcp_call(&self.server_config.name);
+
+        if let Some(max_calls) = query_state.max_mcp_calls()
+            && calls > max_calls
+        {
+            return resources_err!(
+                "the statement reached the limit of {} MCP calls. (limits.max_mcp_calls or SET dataharpoon.max_mcp_calls)",
+                max_calls
+            );
+        }
+        if let Some(max_calls) = self.server_config.max_calls_per_statement
+            && server_calls > max_calls
+        {
+            return resources_err!(
+                "the statement reached the limit of {} calls to mcp-server({}). (max_calls_per_statement)",
+                max_calls,
+                self.server_config.name
+            );
+        }
+        Ok(())
+    }
+
+    /// Retries the calls the server rejected for rate limiting, waiting twice as long each time.
+    /// The wait holds back the other calls to the server too. A transport error may come after the
+    /// tool ran, so it is retried only for tools annotated as read-only or idempotent.
+    async fn call_tool_with_backoff(
+        &self,
+        tool_name: &str,
+        arguments: Option<JsonObject>,
+    ) -> datafusion::common::Result<CallToolResult> {
+        let cli = McpClient::new(self.server_config.clone());
+        let rate_limiter = &self.runtime.rate_limiter;
+        let mut backoff = INITIAL_BACKOFF;
+
+        for _ in 0..MAX_RATE_LIMITED_RETRIES {
+            self.count_call()?;
+            rate_limiter.acquire(&self.server_config).await;
+            let response = cli.call_tool(tool_name, arguments.clone()).await;
+            if !self.is_retryable(tool_name, &response).await {
+                return response;
+            }
+
+            rate_limiter.pause(&self.server_config, backoff);
+            backoff *= 2;
+        }
+
+        self.count_call()?;
+        rate_limiter.acquire(&self.server_config).await;
+        cli.call_tool(tool_name, arguments).await
+    }
+
+    async fn is_retryable(
+        &self,
+        tool_name: &str,
+        response: &datafusion::common::Result<CallToolResult>,
+    ) -> bool {
+        let message = match response {
+            Ok(response) if response.is_error == Some(true) => match response.content.first() {
+                Some(content) => match &content.raw {
+                    Text(text) => text.text.clone(),
+                    _ => return false,
+                },
+                None => return false,
+            },
+            Ok(_) => return false,
+            Err(e) => {
+                if !self.is_safe_to_repeat(tool_name).await {
+                    return false;
+                }
+                e.to_string()
+            }
+        };
+
+        Self::is_rate_limited(&message)
+    }
+
+    /// Servers tell rate limiting only by the message, such as `429 Too Many Requests`.
+    fn is_rate_limited(message: &str) -> bool {
+        let message = message.to_lowercase().replace(['_', '-'], " ");
+        RATE_LIMITED_REGEX.is_match(&message)
+    }
+
+    async fn is_safe_to_repeat(&self, tool_name: &str) -> bool {
+        let Ok(Some(tool)) = self
+            .runtime
+            .tool_policy
+            .tool(&self.server_config, tool_name)
+            .await
+        else {
+            return false;
+        };
+
+        tool.annotations.is_some_and(|annotations| {
+            annotations.read_only_hint == Some(true) || annotations.idempotent_hint == Some(true)
+        })
+    }
+
+    pub async fn call_as_values(
         &self,
         tool_name: String,
         arguments: Option<JsonObject>,
     ) -> datafusion::common::Result<Vec<Value>> {
-        let text = self.call_as_string(tool_name, arguments)?;
+        let text = self.call_as_string(tool_name, arguments).await?;
 
         self.to_values(text.as_str())
     }
@@ -94,3 +287,22 @@ impl McpToolCaller {
         Ok(values)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_rate_limited_needs_a_rate_limit_message() {
+        assert!(McpToolCaller::is_rate_limited("429 Too Many Requests"));
+        assert!(McpToolCaller::is_rate_limited("HTTP 429: rate exceeded"));
+        assert!(McpToolCaller::is_rate_limited("RATE_LIMITED"));
+        assert!(McpToolCaller::is_rate_limited("API rate-limit exceeded"));
+        assert!(!McpToolCaller::is_rate_limited("status 429"));
+        assert!(!McpToolCaller::is_rate_limited(
+            "issue 4290 has too many labels"
+        ));
+        assert!(!McpToolCaller::is_rate_limited("order 1429 is not found"));
+        assert!(!McpToolCaller::is_rate_limited("not found"));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::config::catalog_config::CatalogConfig;
+use datafusion::common::TableReference;
+use datafusion::execution::SessionState;
+use datafusion::logical_expr::{DdlStatement, LogicalPlan};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogEntryKind {
+    View,
+    ExternalTable,
+}
+
+/// A statement creating a view or an external table, replayed at the start.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    /// Resolved name such as `datafusion.public.my_view`.
+    pub name: String,
+    pub kind: CatalogEntryKind,
+    pub sql: String,
+}
+
+/// Change to the catalog made by a DDL statement.
+#[derive(Debug)]
+pub enum CatalogChange {
+    Create(CatalogEntry),
+    Drop(String),
+}
+
+/// Keeps the definitions of views and external tables in a JSON file so that they survive
+/// restarts. Temporary ones aren't stored.
+#[derive(Debug)]
+pub struct PersistentCatalog {
+    file: Option<PathBuf>,
+    entries: Mutex<Vec<CatalogEntry>>,
+}
+
+impl PersistentCatalog {
+    pub fn new(config: &CatalogConfig) -> Self {
+        let file = config.catalog_file();
+        let entries = file.as_deref().map(Self::load_entries).unwrap_or_default();
+
+        Self {
+            file,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// Entries in the created order, so that views are created after the tables they refer to.
+    pub fn entries(&self) -> Vec<CatalogEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Returns the change the plan makes to the catalog when it's persisted.
+    pub fn change_of(
+        &self,
+        plan: &LogicalPlan,
+        sql: &str,
+        state: &SessionState,
+    ) -> Option<CatalogChange> {
+        self.file.as_ref()?;
+
+        let options = state.config().options();
+        let resolve = |name: &TableReference| {
+            name.clone().resolve(
+                &options.catalog.default_catalog,
+                &options.catalog.default_schema,
+            )
+        };
+        let exists = |name: &TableReference| {
+            let name = resolve(name);
+            state
+                .catalog_list()
+                .catalog(&name.catalog)
+                .and_then(|catalog| catalog.schema(&name.schema))
+                .is_some_and(|schema| schema.table_exist(&name.table))
+        };
+        let create = |name: &TableReference, kind: CatalogEntryKind| {
+            Some(CatalogChange::Create(CatalogEntry {
+                name: resolve(name).to_string(),
+                kind,
+                sql: sql.trim().trim_end_matches(';').to_string(),
+            }))
+        };
+
+        let LogicalPlan::Ddl(ddl) = plan else {
+            return None;
+        };
+        match ddl {
+            DdlStatement::CreateView(cmd) if !cmd.temporary => {
+                create(&cmd.name, CatalogEntryKind::View)
+            }
+            // `IF NOT EXISTS` leaves the existing table, so its entry stays as well.
+            DdlStatement::CreateExternalTable(cmd) if cmd.if_not_exists && exists(&cmd.name) => {
+                None
+            }
+            DdlStatement::CreateExternalTable(cmd) if !cmd.temporary => {
+                create(&cmd.name, CatalogEntryKind::ExternalTable)
+            }
+            DdlStatement::DropView(cmd) => {
+                Some(CatalogChange::Drop(resolve(&cmd.name).to_string()))
+            }
+            DdlStatement::DropTable(cmd) => {
+                Some(CatalogChange::Drop(resolve(&cmd.name).to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Applies the change made by a succeeded statement and writes the file.
+    pub fn apply(&self, change: CatalogChange) {
+        let Some(path) = &self.file else {
+            return;
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        match change {
+            CatalogChange::Create(entry) => {
+                match entries.iter_mut().find(|e| e.name == entry.name) {
+                    // `CREATE OR REPLACE` keeps the position so that the dependents still follow it.
+                    Some(existing) => *existing = entry,
+                    None => entries.push(entry),
+                }
+            }
+            CatalogChange::Drop(name) => entries.retain(|e| e.name != name),
+        }
+
+        if let Err(e) = Self::write_entries(path, &entries) {
+            warn!("failed to write catalog file({}). {}", path.display(), e);
+        }
+    }
+
+    fn load_entries(path: &Path) -> Vec<CatalogEntry> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return vec![],
+            Err(e) => {
+                warn!("failed to read catalog file({}). {}", path.display(), e);
+                return vec![];
+            }
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("failed to parse catalog file({}). {}", path.display(), e);
+            vec![]
+        })
+    }
+
+    /// Writes to a temporary file and renames it, so that a crash doesn't leave a broken file.
+    fn write_entries(path: &Path, entries: &[CatalogEntry]) -> std::io::Result<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(entries)?;
+        let temporary = path.with_extension("json.tmp");
+        fs::write(&temporary, content)?;
+        fs::rename(&temporary, path)
+    }
+}
@@ -0,0 +1,196 @@
+use crate::config::query_history_config::QueryHistoryConfig;
+use crate::model::engine::query_source::QuerySource;
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryStatus {
+    Success,
+    Error,
+}
+
+impl QueryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueryStatus::Success => "success",
+            QueryStatus::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryHistoryRecord {
+    pub query_id: String,
+    pub source: QuerySource,
+    pub sql: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub row_count: Option<i64>,
+    pub status: QueryStatus,
+    pub error_message: Option<String>,
+}
+
+/// Keeps executed statements, including the ones from the previous sessions when persisted.
+#[derive(Debug)]
+pub struct QueryHistory {
+    records: Mutex<VecDeque<QueryHistoryRecord>>,
+    max_records: usize,
+    file: Option<Mutex<HistoryFile>>,
+}
+
+#[derive(Debug)]
+struct HistoryFile {
+    path: PathBuf,
+    file: File,
+    /// Records in the file, including the ones no longer kept.
+    lines: usize,
+}
+
+impl QueryHistory {
+    pub fn new(config: &QueryHistoryConfig) -> Self {
+        let mut records = VecDeque::with_capacity(config.max_records);
+        let mut file = None;
+
+        if let Some(path) = config.history_file() {
+            let lines = Self::load_records(&path, &mut records, config.max_records);
+
+            file = match Self::open_file(path.clone(), &records, lines) {
+                Ok(f) => Some(Mutex::new(f)),
+                Err(e) => {
+                    warn!(
+                        "failed to open query history file({}). {}",
+                        path.display(),
+                        e
+                    );
+                    None
+                }
+            };
+        }
+
+        Self {
+            records: Mutex::new(records),
+            max_records: config.max_records,
+            file,
+        }
+    }
+
+    pub fn record(&self, record: QueryHistoryRecord) {
+        let mut records = self.records.lock().unwrap();
+        self.append_to_file(&record, &records);
+        Self::push_bounded(&mut records, record, self.max_records);
+    }
+
+    pub fn records(&self) -> Vec<QueryHistoryRecord> {
+        self.records.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn push_bounded(
+        records: &mut VecDeque<QueryHistoryRecord>,
+        record: QueryHistoryRecord,
+        max_records: usize,
+    ) {
+        if max_records == 0 {
+            return;
+        }
+        while records.len() >= max_records {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// Loads the latest records of the file. Returns the number of lines in it.
+    fn load_records(
+        path: &Path,
+        records: &mut VecDeque<QueryHistoryRecord>,
+        max_records: usize,
+    ) -> usize {
+        let Ok(file) = File::open(path) else {
+            return 0;
+        };
+
+        let mut lines = 0;
+        for line in BufReader::new(file).lines().map_while(|line| line.ok()) {
+            lines += 1;
+            match serde_json::from_str(&line) {
+                Ok(record) => Self::push_bounded(records, record, max_records),
+                Err(e) => warn!("skip broken query history. {}. line: {}", e, line),
+            }
+        }
+
+        lines
+    }
+
+    /// Opens the file to append, after cutting it down to `records` if it has more lines.
+    fn open_file(
+        path: PathBuf,
+        records: &VecDeque<QueryHistoryRecord>,
+        lines: usize,
+    ) -> std::io::Result<HistoryFile> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if lines > records.len() {
+            return Self::rewrite_file(path, records);
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(HistoryFile { path, file, lines })
+    }
+
+    /// Replaces the file with `records`, through a temporary file so that a crash keeps the old one.
+    fn rewrite_file(
+        path: PathBuf,
+        records: &VecDeque<QueryHistoryRecord>,
+    ) -> std::io::Result<HistoryFile> {
+        let temp_path = path.with_extension("jsonl.tmp");
+        let mut temp_file = File::create(&temp_path)?;
+        for record in records {
+            let line = serde_json::to_string(record).map_err(std::io::Error::other)?;
+            writeln!(temp_file, "{}", line)?;
+        }
+        temp_file.sync_all()?;
+        fs::rename(&temp_path, &path)?;
+
+        let file = OpenOptions::new().append(true).open(&path)?;
+        Ok(HistoryFile {
+            path,
+            file,
+            lines: records.len(),
+        })
+    }
+
+    fn append_to_file(&self, record: &QueryHistoryRecord, records: &VecDeque<QueryHistoryRecord>) {
+        let Some(file) = &self.file else {
+            return;
+        };
+
+        let line = match serde_json::to_string(record) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("failed to serialize query history. {}", e);
+                return;
+            }
+        };
+
+        let mut file = file.lock().unwrap();
+        if file.lines >= self.max_records.max(1) * 2 {
+            match Self::rewrite_file(file.path.clone(), records) {
+                Ok(rewritten) => *file = rewritten,
+                Err(e) => warn!("failed to compact query history file. {}", e),
+            }
+        }
+        if let Err(e) = writeln!(file.file, "{}", line) {
+            warn!("failed to write query history file. {}", e);
+            return;
+        }
+        file.lines += 1;
+    }
+}
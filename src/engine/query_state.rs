@@ -0,0 +1,140 @@
+use crate::model::engine::query_source::QuerySource;
+use datafusion::common::config::{ConfigEntry, ConfigExtension, ConfigOptions, ExtensionOptions};
+use datafusion::common::{Result, config_err};
+use serde_json::Value;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// State of a statement, shared by the MCP calls it makes. Each statement has its own, so
+/// statements running at the same time don't see each other's confirmations and call counts.
+#[derive(Debug, Default)]
+pub struct QueryState {
+    query_id: Option<String>,
+    source: Option<QuerySource>,
+    /// Calls to MCP servers the statement can make. Unlimited when None.
+    max_mcp_calls: Option<usize>,
+    confirmed_tools: Mutex<HashSet<(String, String)>>,
+    /// Calls to MCP servers made by the statement, by the server.
+    mcp_calls: Mutex<HashMap<String, usize>>,
+    /// Responses of the tools called while planning, by the call. The execution takes them
+    /// rather than calling the tool again.
+    planned_responses: Mutex<HashMap<String, Vec<Value>>>,
+    /// Set when the statement ends, so that work left running, such as the calls blocking on
+    /// a timed-out statement, stops calling tools.
+    finished: AtomicBool,
+}
+
+impl QueryState {
+    pub fn new(
+        query_id: String,
+        source: Option<QuerySource>,
+        max_mcp_calls: Option<usize>,
+    ) -> Self {
+        Self {
+            query_id: Some(query_id),
+            source,
+            max_mcp_calls,
+            ..Default::default()
+        }
+    }
+
+    pub fn query_id(&self) -> Option<String> {
+        self.query_id.clone()
+    }
+
+    pub fn source(&self) -> Option<QuerySource> {
+        self.source
+    }
+
+    pub fn max_mcp_calls(&self) -> Option<usize> {
+        self.max_mcp_calls
+    }
+
+    /// Counts a call to the server. Returns the calls of the statement and the ones to the server.
+    pub fn add_mcp_call(&self, server_name: &str) -> (usize, usize) {
+        let mut mcp_calls = self.mcp_calls.lock().unwrap();
+        let server_calls = mcp_calls.entry(server_name.to_string()).or_default();
+        *server_calls += 1;
+        let server_calls = *server_calls;
+
+        (mcp_calls.values().sum(), server_calls)
+    }
+
+    pub fn is_confirmed(&self, server_name: &str, tool_name: &str) -> bool {
+        self.confirmed_tools
+            .lock()
+            .unwrap()
+            .contains(&(server_name.to_string(), tool_name.to_string()))
+    }
+
+    pub fn confirm(&self, server_name: &str, tool_name: &str) {
+        self.confirmed_tools
+            .lock()
+            .unwrap()
+            .insert((server_name.to_string(), tool_name.to_string()));
+    }
+
+    pub fn plan_response(&self, key: String, values: Vec<Value>) {
+        self.planned_responses.lock().unwrap().insert(key, values);
+    }
+
+    pub fn take_planned_response(&self, key: &str) -> Option<Vec<Value>> {
+        self.planned_responses.lock().unwrap().remove(key)
+    }
+
+    /// Drops the responses the execution didn't take, e.g. when the statement failed or timed out,
+    /// and stops the calls still left.
+    pub fn finish(&self) {
+        self.finished.store(true, Ordering::Relaxed);
+        self.planned_responses.lock().unwrap().clear();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+}
+
+/// Carries the state of the statement in its session options, so that the plans and the
+/// functions find it while planning and executing the statement.
+#[derive(Debug, Clone, Default)]
+pub struct QueryStateExtension(pub Arc<QueryState>);
+
+impl QueryStateExtension {
+    /// The state of the statement, or a new one for plans run outside of statements, such as
+    /// restoring the catalog.
+    pub fn query_state(options: &ConfigOptions) -> Arc<QueryState> {
+        options
+            .extensions
+            .get::<Self>()
+            .map(|extension| extension.0.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl ExtensionOptions for QueryStateExtension {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn cloned(&self) -> Box<dyn ExtensionOptions> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, key: &str, _value: &str) -> Result<()> {
+        config_err!("{}.{} can't be set", Self::PREFIX, key)
+    }
+
+    fn entries(&self) -> Vec<ConfigEntry> {
+        vec![]
+    }
+}
+
+impl ConfigExtension for QueryStateExtension {
+    const PREFIX: &'static str = "dataharpoon_statement";
+}
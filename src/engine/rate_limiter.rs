@@ -0,0 +1,75 @@
+use crate::config::mcp_server_config::McpServerConfig;
+use dashmap::DashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Token buckets of the servers, shared by every call so that the queries and the agent take
+/// turns on the same rate.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: DashMap<String, Arc<Mutex<Bucket>>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    /// Set when the server said it is rate limited. Calls wait until then.
+    paused_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Waits for the turn of a call. Each call takes a token even when it waits, so the calls run
+    /// in the order they came.
+    pub async fn acquire(&self, config: &McpServerConfig) {
+        let rate_limit = config.rate_limit.clone().unwrap_or_default();
+        let bucket = self.bucket(config, rate_limit.burst());
+
+        let wait = {
+            let mut bucket = bucket.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let paused = bucket
+                .paused_until
+                .map(|until| until.saturating_duration_since(now))
+                .unwrap_or_default();
+
+            match rate_limit.rate() {
+                Some(rate) => {
+                    let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+                    bucket.tokens = (bucket.tokens + elapsed * rate).min(rate_limit.burst()) - 1.0;
+                    bucket.updated_at = now;
+                    let throttled = Duration::from_secs_f64((-bucket.tokens).max(0.0) / rate);
+                    paused.max(throttled)
+                }
+                None => paused,
+            }
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Holds the calls to the server back for `duration`.
+    pub fn pause(&self, config: &McpServerConfig, duration: Duration) {
+        let burst = config.rate_limit.clone().unwrap_or_default().burst();
+        let bucket = self.bucket(config, burst);
+        let mut bucket = bucket.lock().unwrap_or_else(|e| e.into_inner());
+
+        let until = Instant::now() + duration;
+        bucket.paused_until = Some(bucket.paused_until.map_or(until, |u| u.max(until)));
+    }
+
+    fn bucket(&self, config: &McpServerConfig, burst: f64) -> Arc<Mutex<Bucket>> {
+        self.buckets
+            .entry(config.name.clone())
+            .or_insert_with(|| {
+                Arc::new(Mutex::new(Bucket {
+                    tokens: burst,
+                    updated_at: Instant::now(),
+                    paused_until: None,
+                }))
+            })
+            .clone()
+    }
+}
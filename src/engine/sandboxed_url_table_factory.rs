@@ -0,0 +1,74 @@
+use crate::engine::access_policy::AccessPolicy;
+use crate::engine::hive_partitions::with_typed_partitions;
+use crate::engine::json_shape::JsonShape;
+use crate::engine::json_table::read_json_documents;
+use crate::engine::udf::read_context::session_state;
+use crate::engine::xlsx_table::{XlsxOptions, read_xlsx_table};
+use async_trait::async_trait;
+use datafusion::catalog::{TableProvider, UrlTableFactory};
+use datafusion::common::Result;
+use datafusion::datasource::dynamic_file::DynamicListTableFactory;
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion_session::SessionStore;
+use std::sync::Arc;
+
+/// Creates tables from file paths and URLs used as table names, checking the access policy first.
+/// JSON documents and xlsx workbooks, which DataFusion can't read, are read as tables too, and
+/// Hive-style partition columns of directories are typed.
+#[derive(Debug)]
+pub(super) struct SandboxedUrlTableFactory {
+    inner: DynamicListTableFactory,
+    access_policy: Arc<AccessPolicy>,
+}
+
+impl SandboxedUrlTableFactory {
+    pub(super) fn new(access_policy: Arc<AccessPolicy>) -> Self {
+        Self {
+            inner: DynamicListTableFactory::new(SessionStore::new()),
+            access_policy,
+        }
+    }
+
+    pub(super) fn session_store(&self) -> &SessionStore {
+        self.inner.session_store()
+    }
+}
+
+#[async_trait]
+impl UrlTableFactory for SandboxedUrlTableFactory {
+    async fn try_new(&self, url: &str) -> Result<Option<Arc<dyn TableProvider>>> {
+        let Ok(table_url) = ListingTableUrl::parse(url) else {
+            return Ok(None);
+        };
+
+        // Every unknown table name reaches here, so keep "table not found" for missing files.
+        if let Some(path) = AccessPolicy::local_path(&table_url)
+            && !path.exists()
+        {
+            return Ok(None);
+        }
+
+        self.access_policy.check_url(url)?;
+
+        if url.to_lowercase().ends_with(".xlsx") {
+            let state = session_state(self.session_store())?;
+            let table = read_xlsx_table(&state, &table_url, &XlsxOptions::default()).await?;
+            return Ok(Some(table));
+        }
+
+        if url.to_lowercase().ends_with(".json") {
+            let state = session_state(self.session_store())?;
+            if let Some(table) =
+                read_json_documents(&state, &table_url, None, &JsonShape::default()).await?
+            {
+                return Ok(Some(table));
+            }
+        }
+
+        let Some(table) = self.inner.try_new(url).await? else {
+            return Ok(None);
+        };
+        let state = session_state(self.session_store())?;
+        Ok(Some(with_typed_partitions(&state, table, false).await?))
+    }
+}
@@ -0,0 +1,33 @@
+use crate::config::source_config::SourceConfig;
+use crate::engine::hive_partitions::with_typed_partitions;
+use datafusion::catalog::TableProvider;
+use datafusion::common::{Result, exec_err};
+use datafusion::execution::SessionState;
+use datafusion::logical_expr::{DdlStatement, LogicalPlan};
+use std::sync::Arc;
+
+/// Creates the table of the source same as `CREATE EXTERNAL TABLE`, typing the partition columns.
+pub async fn create_source_table(
+    state: &SessionState,
+    source: &SourceConfig,
+) -> Result<Arc<dyn TableProvider>> {
+    let table = create_external_table(state, &source.name, &source.create_table_sql()).await?;
+    with_typed_partitions(state, table, source.hive_partitioning).await
+}
+
+/// Creates the table by the factory of `CREATE EXTERNAL TABLE` without registering it.
+pub async fn create_external_table(
+    state: &SessionState,
+    name: &str,
+    sql: &str,
+) -> Result<Arc<dyn TableProvider>> {
+    let plan = state.create_logical_plan(sql).await?;
+    let LogicalPlan::Ddl(DdlStatement::CreateExternalTable(cmd)) = plan else {
+        return exec_err!("unexpected plan for {}. {}", name, plan);
+    };
+    let Some(factory) = state.table_factories().get(cmd.file_type.as_str()) else {
+        return exec_err!("unsupported file type for {}. {}", name, cmd.file_type);
+    };
+
+    factory.create(state, &cmd).await
+}
@@ -0,0 +1,6 @@
+pub mod sqlite_attach;
+mod sqlite_catalog_provider;
+mod sqlite_connection;
+mod sqlite_filter;
+mod sqlite_schema_provider;
+mod sqlite_table_provider;
@@ -0,0 +1,75 @@
+use crate::engine::access_policy::AccessPolicy;
+use crate::engine::sqlite::sqlite_catalog_provider::SqliteCatalogProvider;
+use datafusion::common::{Result, plan_err};
+use datafusion::dataframe::DataFrame;
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::logical_expr::LogicalPlanBuilder;
+use datafusion::prelude::SessionContext;
+use datafusion::sql::parser::Statement;
+use datafusion::sql::sqlparser::ast::{Expr, Statement as SQLStatement, Value, ValueWithSpan};
+use std::sync::Arc;
+
+/// Attaches the SQLite database as a catalog named `name`.
+pub fn attach_sqlite(context: &SessionContext, name: &str, path: &str) -> Result<()> {
+    let default_catalog = context
+        .copied_config()
+        .options()
+        .catalog
+        .default_catalog
+        .clone();
+    if name == default_catalog {
+        return plan_err!("{} is the default catalog. Attach with another name", name);
+    }
+
+    let table_url = ListingTableUrl::parse(path)?;
+    let Some(path) = AccessPolicy::local_path(&table_url) else {
+        return plan_err!("only local SQLite files can be attached. path: {}", path);
+    };
+
+    let catalog = SqliteCatalogProvider::try_new(path)?;
+    context.register_catalog(name, Arc::new(catalog));
+
+    Ok(())
+}
+
+/// Runs `ATTACH 'app.db' AS app`, which DataFusion doesn't support, if the statement is.
+pub fn try_attach_statement(
+    context: &SessionContext,
+    access_policy: &AccessPolicy,
+    statement: &Statement,
+) -> Result<Option<DataFrame>> {
+    let Statement::Statement(statement) = statement else {
+        return Ok(None);
+    };
+    let SQLStatement::AttachDatabase {
+        schema_name,
+        database_file_name,
+        ..
+    } = statement.as_ref()
+    else {
+        return Ok(None);
+    };
+
+    let Expr::Value(ValueWithSpan {
+        value: Value::SingleQuotedString(path),
+        ..
+    }) = database_file_name
+    else {
+        return plan_err!(
+            "ATTACH takes a path of a SQLite database. e.g. ATTACH 'app.db' AS app. input: {}",
+            database_file_name
+        );
+    };
+    access_policy.check_read_only_statement("ATTACH")?;
+    access_policy.check_url(path)?;
+
+    // Same as the other identifiers, unquoted names are lowercased.
+    let name = match schema_name.quote_style {
+        Some(_) => schema_name.value.clone(),
+        None => schema_name.value.to_lowercase(),
+    };
+    attach_sqlite(context, &name, path)?;
+
+    let plan = LogicalPlanBuilder::empty(false).build()?;
+    Ok(Some(DataFrame::new(context.state(), plan)))
+}
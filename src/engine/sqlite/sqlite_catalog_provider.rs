@@ -0,0 +1,44 @@
+use crate::engine::sqlite::sqlite_connection::{list_tables, open};
+use crate::engine::sqlite::sqlite_schema_provider::SqliteSchemaProvider;
+use datafusion::catalog::{CatalogProvider, SchemaProvider};
+use datafusion::common::Result;
+use std::any::Any;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const MAIN_SCHEMA_NAME: &str = "main";
+
+/// A SQLite database attached as a catalog. Its tables are in the `main` schema,
+/// e.g. `SELECT * FROM app.main.users`.
+#[derive(Debug)]
+pub struct SqliteCatalogProvider {
+    main: Arc<SqliteSchemaProvider>,
+}
+
+impl SqliteCatalogProvider {
+    /// Opens the database once to fail early for missing files and non-SQLite files.
+    pub fn try_new(path: PathBuf) -> Result<Self> {
+        list_tables(&open(&path)?)?;
+
+        Ok(Self {
+            main: Arc::new(SqliteSchemaProvider::new(path)),
+        })
+    }
+}
+
+impl CatalogProvider for SqliteCatalogProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema_names(&self) -> Vec<String> {
+        vec![MAIN_SCHEMA_NAME.to_string()]
+    }
+
+    fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
+        match name {
+            MAIN_SCHEMA_NAME => Some(self.main.clone()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use datafusion::arrow::array::{
+    ArrayBuilder, ArrayRef, BinaryBuilder, BooleanBuilder, Float64Builder, Int64Builder,
+    RecordBatch, RecordBatchOptions, StringBuilder,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::common::{DataFusionError, Result, exec_datafusion_err, exec_err};
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use std::path::Path;
+use std::sync::Arc;
+
+const LIST_TABLES_SQL: &str = "SELECT name FROM sqlite_master WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY name";
+
+/// Opens the database read-only. LIKE is made case-sensitive to behave the same as DataFusion.
+pub(super) fn open(path: &Path) -> Result<Connection> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| exec_datafusion_err!("failed to open sqlite({}). {}", path.display(), e))?;
+    conn.pragma_update(None, "case_sensitive_like", true)
+        .map_err(sqlite_error)?;
+
+    Ok(conn)
+}
+
+pub(super) fn list_tables(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(LIST_TABLES_SQL).map_err(sqlite_error)?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(sqlite_error)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(sqlite_error)?;
+
+    Ok(names)
+}
+
+/// Returns the schema of the table or view, None if it doesn't exist.
+pub(super) fn table_schema(conn: &Connection, table: &str) -> Result<Option<SchemaRef>> {
+    let sql = format!("PRAGMA table_info({})", quote_identifier(table));
+    let mut stmt = conn.prepare(&sql).map_err(sqlite_error)?;
+    let fields = stmt
+        .query_map([], |row| {
+            let name: String = row.get(1)?;
+            let declared_type: String = row.get(2)?;
+            Ok(Field::new(name, to_data_type(&declared_type), true))
+        })
+        .map_err(sqlite_error)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(sqlite_error)?;
+
+    if fields.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Arc::new(Schema::new(fields))))
+}
+
+/// Maps the declared column type by the SQLite affinity rules. Dates are usually stored as text.
+fn to_data_type(declared_type: &str) -> DataType {
+    let declared_type = declared_type.to_uppercase();
+    let contains = |words: &[&str]| words.iter().any(|w| declared_type.contains(w));
+
+    if contains(&["INT"]) {
+        DataType::Int64
+    } else if declared_type.is_empty() || contains(&["CHAR", "CLOB", "TEXT", "DATE", "TIME"]) {
+        DataType::Utf8
+    } else if contains(&["BLOB"]) {
+        DataType::Binary
+    } else if contains(&["BOOL"]) {
+        DataType::Boolean
+    } else {
+        DataType::Float64
+    }
+}
+
+/// Runs the query and passes the rows in batches, until `on_batch` returns false.
+/// Values not matching the column type are converted when possible, otherwise they are null.
+pub(super) fn read_batches(
+    path: &Path,
+    sql: &str,
+    schema: &SchemaRef,
+    batch_size: usize,
+    mut on_batch: impl FnMut(RecordBatch) -> bool,
+) -> Result<()> {
+    let conn = open(path)?;
+    let mut stmt = conn.prepare(sql).map_err(sqlite_error)?;
+    let mut rows = stmt.query([]).map_err(sqlite_error)?;
+
+    let mut builders = new_builders(schema, batch_size)?;
+    let mut row_count = 0;
+    while let Some(row) = rows.next().map_err(sqlite_error)? {
+        for (i, builder) in builders.iter_mut().enumerate() {
+            append_value(builder, row.get_ref(i).map_err(sqlite_error)?);
+        }
+        row_count += 1;
+
+        if row_count == batch_size {
+            if !on_batch(finish_batch(schema, &mut builders, row_count)?) {
+                return Ok(());
+            }
+            row_count = 0;
+        }
+    }
+    if row_count > 0 {
+        on_batch(finish_batch(schema, &mut builders, row_count)?);
+    }
+
+    Ok(())
+}
+
+enum ColumnBuilder {
+    Int64(Int64Builder),
+    Float64(Float64Builder),
+    Boolean(BooleanBuilder),
+    Utf8(StringBuilder),
+    Binary(BinaryBuilder),
+}
+
+fn new_builders(schema: &SchemaRef, capacity: usize) -> Result<Vec<ColumnBuilder>> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| match field.data_type() {
+            DataType::Int64 => Ok(ColumnBuilder::Int64(Int64Builder::with_capacity(capacity))),
+            DataType::Float64 => Ok(ColumnBuilder::Float64(Float64Builder::with_capacity(
+                capacity,
+            ))),
+            DataType::Boolean => Ok(ColumnBuilder::Boolean(BooleanBuilder::with_capacity(
+                capacity,
+            ))),
+            DataType::Utf8 => Ok(ColumnBuilder::Utf8(StringBuilder::new())),
+            DataType::Binary => Ok(ColumnBuilder::Binary(BinaryBuilder::new())),
+            data_type => exec_err!("unsupported type for sqlite: {}", data_type),
+        })
+        .collect()
+}
+
+fn append_value(builder: &mut ColumnBuilder, value: ValueRef) {
+    match builder {
+        ColumnBuilder::Int64(b) => b.append_option(match value {
+            ValueRef::Integer(i) => Some(i),
+            ValueRef::Real(f) => Some(f as i64),
+            ValueRef::Text(t) => String::from_utf8_lossy(t).trim().parse().ok(),
+            _ => None,
+        }),
+        ColumnBuilder::Float64(b) => b.append_option(match value {
+            ValueRef::Integer(i) => Some(i as f64),
+            ValueRef::Real(f) => Some(f),
+            ValueRef::Text(t) => String::from_utf8_lossy(t).trim().parse().ok(),
+            _ => None,
+        }),
+        ColumnBuilder::Boolean(b) => b.append_option(match value {
+            ValueRef::Integer(i) => Some(i != 0),
+            ValueRef::Real(f) => Some(f != 0.0),
+            ValueRef::Text(t) => match String::from_utf8_lossy(t).trim().to_lowercase().as_str() {
+                "true" | "1" => Some(true),
+                "false" | "0" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }),
+        ColumnBuilder::Utf8(b) => b.append_option(match value {
+            ValueRef::Integer(i) => Some(i.to_string()),
+            ValueRef::Real(f) => Some(f.to_string()),
+            ValueRef::Text(t) | ValueRef::Blob(t) => Some(String::from_utf8_lossy(t).to_string()),
+            ValueRef::Null => None,
+        }),
+        ColumnBuilder::Binary(b) => match value {
+            ValueRef::Text(t) | ValueRef::Blob(t) => b.append_value(t),
+            _ => b.append_null(),
+        },
+    }
+}
+
+fn finish_batch(
+    schema: &SchemaRef,
+    builders: &mut [ColumnBuilder],
+    row_count: usize,
+) -> Result<RecordBatch> {
+    let columns: Vec<ArrayRef> = builders
+        .iter_mut()
+        .map(|builder| match builder {
+            ColumnBuilder::Int64(b) => ArrayBuilder::finish(b),
+            ColumnBuilder::Float64(b) => ArrayBuilder::finish(b),
+            ColumnBuilder::Boolean(b) => ArrayBuilder::finish(b),
+            ColumnBuilder::Utf8(b) => ArrayBuilder::finish(b),
+            ColumnBuilder::Binary(b) => ArrayBuilder::finish(b),
+        })
+        .collect();
+
+    // Columns can be empty for `count(*)`, so the row count is given explicitly.
+    let options = RecordBatchOptions::new().with_row_count(Some(row_count));
+    Ok(RecordBatch::try_new_with_options(
+        schema.clone(),
+        columns,
+        &options,
+    )?)
+}
+
+pub(super) fn quote_identifier(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+fn sqlite_error(e: rusqlite::Error) -> DataFusionError {
+    exec_datafusion_err!("sqlite error. {}", e)
+}
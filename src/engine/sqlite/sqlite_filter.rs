@@ -0,0 +1,111 @@
+use crate::engine::sqlite::sqlite_connection::quote_identifier;
+use datafusion::logical_expr::expr::InList;
+use datafusion::logical_expr::{Between, BinaryExpr, Expr, Like, Operator};
+use datafusion::scalar::ScalarValue;
+
+/// Translates the filter to a SQLite expression. None if SQLite can't evaluate it the same way as
+/// DataFusion, then DataFusion applies it instead.
+pub(super) fn to_sqlite_expr(expr: &Expr) -> Option<String> {
+    let sql = match expr {
+        Expr::Column(column) => quote_identifier(&column.name),
+        Expr::Literal(value) => to_sqlite_literal(value)?,
+        Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+            let op = match op {
+                Operator::Eq => "=",
+                Operator::NotEq => "<>",
+                Operator::Lt => "<",
+                Operator::LtEq => "<=",
+                Operator::Gt => ">",
+                Operator::GtEq => ">=",
+                Operator::And => "AND",
+                Operator::Or => "OR",
+                _ => return None,
+            };
+            format!(
+                "{} {} {}",
+                to_sqlite_expr(left)?,
+                op,
+                to_sqlite_expr(right)?
+            )
+        }
+        Expr::Not(expr) => format!("NOT {}", to_sqlite_expr(expr)?),
+        Expr::IsNull(expr) => format!("{} IS NULL", to_sqlite_expr(expr)?),
+        Expr::IsNotNull(expr) => format!("{} IS NOT NULL", to_sqlite_expr(expr)?),
+        Expr::Between(Between {
+            expr,
+            negated,
+            low,
+            high,
+        }) => format!(
+            "{} {}BETWEEN {} AND {}",
+            to_sqlite_expr(expr)?,
+            if *negated { "NOT " } else { "" },
+            to_sqlite_expr(low)?,
+            to_sqlite_expr(high)?
+        ),
+        Expr::InList(InList {
+            expr,
+            list,
+            negated,
+        }) => {
+            let list = list
+                .iter()
+                .map(to_sqlite_expr)
+                .collect::<Option<Vec<_>>>()?;
+            format!(
+                "{} {}IN ({})",
+                to_sqlite_expr(expr)?,
+                if *negated { "NOT " } else { "" },
+                list.join(", ")
+            )
+        }
+        Expr::Like(Like {
+            negated,
+            expr,
+            pattern,
+            escape_char,
+            case_insensitive: false,
+        }) => {
+            let escape = match escape_char {
+                Some(c) => format!(" ESCAPE {}", quote_literal(&c.to_string())),
+                None => "".to_string(),
+            };
+            format!(
+                "{} {}LIKE {}{}",
+                to_sqlite_expr(expr)?,
+                if *negated { "NOT " } else { "" },
+                to_sqlite_expr(pattern)?,
+                escape
+            )
+        }
+        _ => return None,
+    };
+
+    Some(format!("({})", sql))
+}
+
+fn to_sqlite_literal(value: &ScalarValue) -> Option<String> {
+    let literal = match value {
+        ScalarValue::Utf8(Some(s))
+        | ScalarValue::LargeUtf8(Some(s))
+        | ScalarValue::Utf8View(Some(s)) => quote_literal(s),
+        ScalarValue::Boolean(Some(b)) => (*b as i64).to_string(),
+        ScalarValue::Float32(Some(f)) if f.is_finite() => f.to_string(),
+        ScalarValue::Float64(Some(f)) if f.is_finite() => f.to_string(),
+        ScalarValue::Int8(Some(_))
+        | ScalarValue::Int16(Some(_))
+        | ScalarValue::Int32(Some(_))
+        | ScalarValue::Int64(Some(_))
+        | ScalarValue::UInt8(Some(_))
+        | ScalarValue::UInt16(Some(_))
+        | ScalarValue::UInt32(Some(_))
+        | ScalarValue::UInt64(Some(_)) => value.to_string(),
+        _ => return None,
+    };
+
+    Some(literal)
+}
+
+fn quote_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
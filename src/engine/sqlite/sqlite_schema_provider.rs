@@ -0,0 +1,61 @@
+use crate::engine::sqlite::sqlite_connection::{list_tables, open, table_schema};
+use crate::engine::sqlite::sqlite_table_provider::SqliteTableProvider;
+use async_trait::async_trait;
+use datafusion::catalog::{SchemaProvider, TableProvider};
+use datafusion::common::Result;
+use log::warn;
+use std::any::Any;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Tables and views of a SQLite database, looked up on each access to follow the changes.
+#[derive(Debug)]
+pub(super) struct SqliteSchemaProvider {
+    path: PathBuf,
+}
+
+impl SqliteSchemaProvider {
+    pub(super) fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+#[async_trait]
+impl SchemaProvider for SqliteSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        match open(&self.path).and_then(|conn| list_tables(&conn)) {
+            Ok(names) => names,
+            Err(e) => {
+                warn!(
+                    "failed to list tables of sqlite({}). {}",
+                    self.path.display(),
+                    e
+                );
+                vec![]
+            }
+        }
+    }
+
+    async fn table(&self, name: &str) -> Result<Option<Arc<dyn TableProvider>>> {
+        let conn = open(&self.path)?;
+        let Some(schema) = table_schema(&conn, name)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Arc::new(SqliteTableProvider::new(
+            self.path.clone(),
+            name.to_string(),
+            schema,
+        ))))
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        open(&self.path)
+            .and_then(|conn| table_schema(&conn, name))
+            .is_ok_and(|schema| schema.is_some())
+    }
+}
@@ -0,0 +1,202 @@
+use crate::engine::sqlite::sqlite_connection::{quote_identifier, read_batches};
+use crate::engine::sqlite::sqlite_filter::to_sqlite_expr;
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::catalog::{Session, TableProvider};
+use datafusion::common::{Result, exec_datafusion_err, project_schema};
+use datafusion::datasource::TableType;
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::{Expr, TableProviderFilterPushDown};
+use datafusion::physical_expr::{EquivalenceProperties, Partitioning};
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use std::any::Any;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// A table or view of a SQLite database. The projection, filters and limit are run by SQLite.
+#[derive(Debug)]
+pub(super) struct SqliteTableProvider {
+    path: PathBuf,
+    table_name: String,
+    schema: SchemaRef,
+}
+
+impl SqliteTableProvider {
+    pub(super) fn new(path: PathBuf, table_name: String, schema: SchemaRef) -> Self {
+        Self {
+            path,
+            table_name,
+            schema,
+        }
+    }
+
+    fn select_sql(
+        &self,
+        schema: &SchemaRef,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<String> {
+        let columns: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|f| quote_identifier(f.name()))
+            .collect();
+        let columns = if columns.is_empty() {
+            "1".to_string()
+        } else {
+            columns.join(", ")
+        };
+
+        let mut sql = format!(
+            "SELECT {} FROM {}",
+            columns,
+            quote_identifier(&self.table_name)
+        );
+        if !filters.is_empty() {
+            let conditions = filters
+                .iter()
+                .map(|f| {
+                    to_sqlite_expr(f)
+                        .ok_or_else(|| exec_datafusion_err!("unsupported filter for sqlite: {}", f))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        Ok(sql)
+    }
+}
+
+#[async_trait]
+impl TableProvider for SqliteTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = project_schema(&self.schema, projection)?;
+        let sql = self.select_sql(&schema, filters, limit)?;
+
+        Ok(Arc::new(SqliteTableExecutionPlan::new(
+            self.path.clone(),
+            sql,
+            schema,
+        )))
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> Result<Vec<TableProviderFilterPushDown>> {
+        Ok(filters
+            .iter()
+            .map(|f| match to_sqlite_expr(f) {
+                Some(_) => TableProviderFilterPushDown::Exact,
+                None => TableProviderFilterPushDown::Unsupported,
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug)]
+struct SqliteTableExecutionPlan {
+    plan_properties: PlanProperties,
+    path: PathBuf,
+    sql: String,
+}
+
+impl SqliteTableExecutionPlan {
+    fn new(path: PathBuf, sql: String, schema: SchemaRef) -> Self {
+        let plan_properties = PlanProperties::new(
+            EquivalenceProperties::new(schema),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        );
+        Self {
+            plan_properties,
+            path,
+            sql,
+        }
+    }
+}
+
+impl DisplayAs for SqliteTableExecutionPlan {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "SqliteTableExecutionPlan: sql={}", self.sql)
+    }
+}
+
+impl ExecutionPlan for SqliteTableExecutionPlan {
+    fn name(&self) -> &str {
+        "SqliteTableExecutionPlan"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        _partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema();
+        let batch_size = context.session_config().batch_size();
+        let (tx, rx) = mpsc::channel(2);
+
+        // SQLite is blocking. Reading stops when the stream is dropped.
+        let path = self.path.clone();
+        let sql = self.sql.clone();
+        let read_schema = schema.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = read_batches(&path, &sql, &read_schema, batch_size, |batch| {
+                tx.blocking_send(Ok(batch)).is_ok()
+            });
+            if let Err(e) = result {
+                tx.blocking_send(Err(e)).ok();
+            }
+        });
+
+        let stream = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|batch| (batch, rx))
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+}
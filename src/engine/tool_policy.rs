@@ -0,0 +1,171 @@
+use crate::config::mcp_server_config::McpServerConfig;
+use crate::config::tool_policy_config::{ToolPolicyAction, ToolPolicyConfig};
+use crate::engine::query_state::QueryState;
+use crate::infra::mcp_client::McpClient;
+use crate::model::engine::query_source::QuerySource;
+use dashmap::DashMap;
+use datafusion::common::{Result, exec_datafusion_err, exec_err};
+use rmcp::model::{Tool, ToolAnnotations};
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// How long the listed tools of a server are used before listing them again.
+const LISTED_TOOLS_TTL: Duration = Duration::from_secs(60);
+
+/// Asks a human whether a tool not annotated as read-only may be called.
+pub trait ToolCallConfirmer: Debug + Send + Sync {
+    /// Blocks until answered. Gives up and returns false once `cancelled` is set, such as when
+    /// the statement times out.
+    fn confirm(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        annotations: Option<&ToolAnnotations>,
+        cancelled: &AtomicBool,
+    ) -> bool;
+}
+
+#[derive(Debug)]
+pub struct ToolPolicy {
+    config: ToolPolicyConfig,
+    listed_tools: DashMap<String, (Instant, Vec<Tool>)>,
+    confirmer: OnceLock<Arc<dyn ToolCallConfirmer>>,
+    confirm_lock: Mutex<()>,
+}
+
+impl ToolPolicy {
+    pub fn new(config: ToolPolicyConfig) -> Self {
+        Self {
+            config,
+            listed_tools: DashMap::new(),
+            confirmer: OnceLock::new(),
+            confirm_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn set_confirmer(&self, confirmer: Arc<dyn ToolCallConfirmer>) {
+        self.confirmer.set(confirmer).ok();
+    }
+
+    pub async fn check(
+        &self,
+        server_config: &Arc<McpServerConfig>,
+        tool_name: &str,
+        query_state: &QueryState,
+    ) -> Result<()> {
+        let action = self.action_for(query_state.source());
+        if action == ToolPolicyAction::Allow {
+            return Ok(());
+        }
+
+        let annotations = self.annotations(server_config, tool_name).await?;
+        if annotations.as_ref().and_then(|a| a.read_only_hint) == Some(true) {
+            return Ok(());
+        }
+
+        let server_name = server_config.name.as_str();
+        match action {
+            ToolPolicyAction::Allow => Ok(()),
+            ToolPolicyAction::Deny => exec_err!(
+                "tool({}) of mcp-server({}) is not annotated as read-only and is denied by the tool policy",
+                tool_name,
+                server_name
+            ),
+            ToolPolicyAction::Confirm => {
+                let _lock = self.confirm_lock.lock().await;
+                if query_state.is_confirmed(server_name, tool_name) {
+                    return Ok(());
+                }
+
+                let Some(confirmer) = self.confirmer.get() else {
+                    return exec_err!(
+                        "tool({}) of mcp-server({}) is not annotated as read-only and requires confirmation, but no one can confirm it",
+                        tool_name,
+                        server_name
+                    );
+                };
+                if !Self::confirm(confirmer.clone(), server_name, tool_name, annotations).await? {
+                    return exec_err!(
+                        "calling tool({}) of mcp-server({}) was rejected",
+                        tool_name,
+                        server_name
+                    );
+                }
+
+                query_state.confirm(server_name, tool_name);
+                Ok(())
+            }
+        }
+    }
+
+    /// Runs the confirmer off the async workers, cancelling it when the check is dropped.
+    async fn confirm(
+        confirmer: Arc<dyn ToolCallConfirmer>,
+        server_name: &str,
+        tool_name: &str,
+        annotations: Option<ToolAnnotations>,
+    ) -> Result<bool> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let _cancel_on_drop = CancelOnDrop(cancelled.clone());
+        let server_name = server_name.to_string();
+        let tool_name = tool_name.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            confirmer.confirm(&server_name, &tool_name, annotations.as_ref(), &cancelled)
+        })
+        .await
+        .map_err(|e| exec_datafusion_err!("failed to confirm the tool call. {}", e))
+    }
+
+    fn action_for(&self, source: Option<QuerySource>) -> ToolPolicyAction {
+        match source {
+            Some(QuerySource::Repl) | Some(QuerySource::Agent) => self.config.repl,
+            Some(QuerySource::McpClient) | None => self.config.mcp_server,
+        }
+    }
+
+    async fn annotations(
+        &self,
+        server_config: &Arc<McpServerConfig>,
+        tool_name: &str,
+    ) -> Result<Option<ToolAnnotations>> {
+        let tool = self.tool(server_config, tool_name).await?;
+        Ok(tool.and_then(|tool| tool.annotations))
+    }
+
+    /// Finds the tool, listing the tools of the server again once the last list gets old.
+    pub async fn tool(
+        &self,
+        server_config: &Arc<McpServerConfig>,
+        tool_name: &str,
+    ) -> Result<Option<Tool>> {
+        let fresh = self
+            .listed_tools
+            .get(&server_config.name)
+            .is_some_and(|entry| entry.0.elapsed() < LISTED_TOOLS_TTL);
+        if !fresh {
+            let cli = McpClient::new(server_config.clone());
+            let tools = cli.list_tools().await?;
+            self.listed_tools
+                .insert(server_config.name.clone(), (Instant::now(), tools));
+        }
+
+        let tool = self
+            .listed_tools
+            .get(&server_config.name)
+            .and_then(|entry| entry.1.iter().find(|tool| tool.name == tool_name).cloned());
+
+        Ok(tool)
+    }
+}
+
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
@@ -1,2 +1,16 @@
+pub mod async_mcp_function;
 pub mod call_mcp_function;
+pub mod exec_mcp_batch_function;
 pub mod exec_mcp_function;
+mod function_options;
+pub mod json_each_function;
+pub mod json_get_function;
+mod json_path;
+pub mod json_query_function;
+pub mod mcp_agg_function;
+pub mod named_arguments;
+pub mod read_context;
+pub mod read_file_function;
+pub mod read_text_function;
+pub mod read_xlsx_function;
+pub mod xlsx_sheets_function;
@@ -0,0 +1,54 @@
+use crate::engine::udf::exec_mcp_batch_function::ExecMcpBatchFunction;
+use crate::engine::udf::exec_mcp_function::ExecMcpFunction;
+use async_trait::async_trait;
+use datafusion::arrow::array::ArrayRef;
+use datafusion::common::{Result, exec_err};
+use datafusion::logical_expr::{ColumnarValue, ScalarUDF};
+use datafusion::physical_expr::PhysicalExpr;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::task::block_in_place;
+
+/// Calls of a query in flight at a time.
+pub const MAX_CONCURRENT_CALLS: usize = 8;
+
+/// Scalar functions calling MCP tools. `ExecMcpExecutionPlan` awaits them rather than blocking.
+#[async_trait]
+pub trait AsyncMcpFunction: Send + Sync {
+    async fn invoke_async(&self, args: &[ColumnarValue], number_rows: usize) -> Result<ArrayRef>;
+
+    /// Calls made for `number_rows` rows, estimated before the execution.
+    fn estimate_calls(&self, _args: &[Arc<dyn PhysicalExpr>], number_rows: usize) -> usize {
+        number_rows
+    }
+}
+
+pub fn as_async_mcp_function(udf: &ScalarUDF) -> Option<&dyn AsyncMcpFunction> {
+    let inner = udf.inner().as_any();
+    if let Some(function) = inner.downcast_ref::<ExecMcpFunction>() {
+        return Some(function);
+    }
+    if let Some(function) = inner.downcast_ref::<ExecMcpBatchFunction>() {
+        return Some(function);
+    }
+    None
+}
+
+/// Runs the calls for `ScalarUDFImpl::invoke_with_args`, which is used only where the function
+/// isn't in a projection or a filter. Table functions use it too, as they are called synchronously
+/// while planning.
+pub(super) fn invoke_blocking<T>(
+    function_name: &str,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            block_in_place(|| handle.block_on(future))
+        }
+        _ => exec_err!(
+            "{} can't be called outside of the multi-threaded runtime",
+            function_name
+        ),
+    }
+}
@@ -1,35 +1,75 @@
 use crate::config::mcp_server_config::McpServerConfig;
+use crate::engine::json_shape::JsonShape;
+use crate::engine::json_table::{infer_schema, schema_properties};
+use crate::engine::mcp_call_table::{McpCall, McpCallSchemaSource, McpCallTable};
+use crate::engine::mcp_runtime::McpRuntime;
 use crate::engine::mcp_tool_caller::McpToolCaller;
+use crate::engine::query_state::QueryState;
+use crate::engine::udf::async_mcp_function::invoke_blocking;
+use crate::engine::udf::function_options::extract_options;
+use crate::engine::udf::read_context::ReadContext;
 use dashmap::DashMap;
-use datafusion::arrow::datatypes::{Schema, SchemaRef};
-use datafusion::arrow::json::ReaderBuilder;
-use datafusion::arrow::json::reader::infer_json_schema;
-use datafusion::catalog::{MemTable, TableFunctionImpl, TableProvider};
-use datafusion::common::{Result, exec_err};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::catalog::{TableFunctionImpl, TableProvider};
+use datafusion::common::Result;
 use datafusion::common::{ScalarValue, plan_err};
-use datafusion::logical_expr::Expr;
+use datafusion::logical_expr::{DdlStatement, Expr, LogicalPlan};
 use rmcp::model::JsonObject;
-use rmcp::serde_json;
-use serde_json::Value;
-use std::io::Cursor;
 use std::sync::Arc;
 
 pub const CALL_MCP_FUNCTION_NAME: &str = "call_mcp";
 
+const CALL_MCP_OPTIONS: &[&str] = &["flatten", "explode", "schema"];
+
+/// Returns the response of the tool as a table. The tool is called when the plan is executed.
+/// e.g. `SELECT * FROM call_mcp('github', 'list_issues', {'repo': 'x'}, flatten => 1, explode => 'labels')`
+///
+/// The columns are the declared `schema`, the output schema of the tool, or the ones of the last
+/// response. Only when none of them is known, a read-only tool is called while planning, and the
+/// execution of the query uses that response.
 #[derive(Debug)]
 pub struct CallMcpFunction {
     servers: DashMap<String, Arc<McpServerConfig>>,
+    runtime: Arc<McpRuntime>,
+    query_state: Arc<QueryState>,
+    /// Whether a read-only tool can be called while planning to find the columns.
+    probe: bool,
+    read_context: ReadContext,
 }
 
 impl CallMcpFunction {
-    pub fn new(servers: DashMap<String, Arc<McpServerConfig>>) -> Self {
-        Self { servers }
+    pub fn new(
+        servers: DashMap<String, Arc<McpServerConfig>>,
+        runtime: Arc<McpRuntime>,
+        read_context: ReadContext,
+    ) -> Self {
+        Self {
+            servers,
+            runtime,
+            query_state: Arc::default(),
+            probe: true,
+            read_context,
+        }
+    }
+
+    /// The same function, planning the calls for the statement of `query_state`.
+    /// `probe` is false for statements only planned, such as `EXPLAIN`.
+    pub fn with_query_state(&self, query_state: Arc<QueryState>, probe: bool) -> Self {
+        Self {
+            servers: self.servers.clone(),
+            runtime: self.runtime.clone(),
+            query_state,
+            probe,
+            read_context: self.read_context.clone(),
+        }
     }
 }
 
 impl TableFunctionImpl for CallMcpFunction {
     fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
         let (server_name, tool_name, tool_arguments) = self.extract_mcp_arguments(args)?;
+        let mut options = extract_options(CALL_MCP_FUNCTION_NAME, args.get(3), CALL_MCP_OPTIONS)?;
+        let shape = JsonShape::take_from(&mut options)?;
 
         let Some(config) = self.servers.get(server_name.as_str()) else {
             return plan_err!(
@@ -39,12 +79,125 @@ impl TableFunctionImpl for CallMcpFunction {
             );
         };
 
-        let tool_caller = McpToolCaller::new(config.clone());
-        // TODO: cache result in the same repl session.
-        let values = tool_caller.call_as_values(tool_name.clone(), tool_arguments)?;
+        if !config.is_tool_allowed(tool_name.as_str()) {
+            return plan_err!(
+                "not allowed tool is used for {}. Server: {}, Tool: {}",
+                CALL_MCP_FUNCTION_NAME,
+                server_name,
+                tool_name
+            );
+        }
+
+        let call = McpCall {
+            server_config: config.clone(),
+            tool_name,
+            arguments: tool_arguments,
+            shape,
+        };
+        let (schema, schema_source) = match options.remove("schema") {
+            Some(columns) => (
+                self.declared_schema(&columns)?,
+                McpCallSchemaSource::Declared,
+            ),
+            None => self.response_schema(&call)?,
+        };
+
+        Ok(Arc::new(McpCallTable::new(
+            call,
+            schema,
+            schema_source,
+            self.runtime.clone(),
+        )))
+    }
+}
+
+impl CallMcpFunction {
+    /// Parses the columns same as the ones of `CREATE EXTERNAL TABLE`.
+    fn declared_schema(&self, columns: &str) -> Result<SchemaRef> {
+        let state = self.read_context.state()?;
+        let sql = format!(
+            "CREATE EXTERNAL TABLE t ({}) STORED AS JSON LOCATION ''",
+            columns
+        );
+        let plan = invoke_blocking(CALL_MCP_FUNCTION_NAME, state.create_logical_plan(&sql))?;
+
+        match plan {
+            LogicalPlan::Ddl(DdlStatement::CreateExternalTable(cmd)) => {
+                Ok(Arc::new(Schema::from(cmd.schema.as_ref())))
+            }
+            _ => plan_err!(
+                "schema for {} must be columns. e.g. schema => 'id BIGINT, title VARCHAR'. input: {}",
+                CALL_MCP_FUNCTION_NAME,
+                columns
+            ),
+        }
+    }
+
+    /// Columns of the response, from the output schema of the tool or the last response.
+    /// Otherwise, calls the tool if it is read-only and keeps the response for the execution.
+    /// Statements only planned get no columns instead of calling the tool.
+    fn response_schema(&self, call: &McpCall) -> Result<(SchemaRef, McpCallSchemaSource)> {
+        let tool = invoke_blocking(
+            CALL_MCP_FUNCTION_NAME,
+            self.runtime
+                .tool_policy
+                .tool(&call.server_config, &call.tool_name),
+        )?;
 
-        let schema = self.infer_schema(&values)?;
-        self.create_table_provider_for(schema, &values, tool_name)
+        if call.shape.is_empty()
+            && let Some(output_schema) = tool.as_ref().and_then(|tool| tool.output_schema.clone())
+        {
+            let fields: Vec<_> = schema_properties(&output_schema)
+                .into_iter()
+                .map(|(name, data_type)| {
+                    Field::new(name, data_type.unwrap_or(DataType::Utf8), true)
+                })
+                .collect();
+            if !fields.is_empty() {
+                return Ok((
+                    Arc::new(Schema::new(fields)),
+                    McpCallSchemaSource::OutputSchema,
+                ));
+            }
+        }
+
+        let key = call.key();
+        if let Some(schema) = self.runtime.response_schemas.get(&key) {
+            return Ok((schema.clone(), McpCallSchemaSource::Probed));
+        }
+
+        if !self.probe {
+            return Ok((Arc::new(Schema::empty()), McpCallSchemaSource::Unknown));
+        }
+        let read_only = tool
+            .and_then(|tool| tool.annotations)
+            .and_then(|annotations| annotations.read_only_hint);
+        if read_only != Some(true) {
+            return plan_err!(
+                "the columns of {}('{}', '{}') are unknown, and the tool isn't called while planning since it isn't annotated as read-only. Declare them. e.g. schema => 'id BIGINT, title VARCHAR'",
+                CALL_MCP_FUNCTION_NAME,
+                call.server_config.name,
+                call.tool_name
+            );
+        }
+
+        let tool_caller = McpToolCaller::new(
+            call.server_config.clone(),
+            self.runtime.clone(),
+            self.query_state.clone(),
+        );
+        let values = invoke_blocking(
+            CALL_MCP_FUNCTION_NAME,
+            tool_caller.call_as_values(call.tool_name.clone(), call.arguments.clone()),
+        )?;
+        let schema = infer_schema(&call.shape.apply(values.clone())?)?;
+
+        self.runtime
+            .response_schemas
+            .insert(key.clone(), schema.clone());
+        self.query_state.plan_response(key, values);
+
+        Ok((schema, McpCallSchemaSource::Probed))
     }
 }
 
@@ -70,7 +223,7 @@ impl CallMcpFunction {
 
     fn extract_mcp_tool_arguments(&self, expr: Option<&Expr>) -> Result<Option<JsonObject>> {
         let f = match expr {
-            None => return Ok(None),
+            None | Some(Expr::Literal(ScalarValue::Null)) => return Ok(None),
             Some(Expr::ScalarFunction(f)) => f,
             _ => {
                 return plan_err!(
@@ -131,38 +284,4 @@ impl CallMcpFunction {
 
         Ok(Some(ret))
     }
-
-    fn infer_schema(&self, values: &Vec<Value>) -> Result<SchemaRef> {
-        let mut schemas = Vec::<Schema>::new();
-        for val in values.iter().take(10) {
-            // TODO: create a way to infer values as is. dumping to json might be slow.
-            let text = serde_json::to_string(val).unwrap();
-            let cursor = Cursor::new(text.clone());
-            let (schema, _) = infer_json_schema(cursor, Some(100))?;
-
-            schemas.push(schema)
-        }
-        let schema = Schema::try_merge(schemas)?;
-
-        Ok(Arc::new(schema))
-    }
-
-    fn create_table_provider_for(
-        &self,
-        schema: SchemaRef,
-        values: &Vec<Value>,
-        tool_name: String,
-    ) -> Result<Arc<dyn TableProvider>> {
-        let mut decoder = ReaderBuilder::new(schema.clone()).build_decoder()?;
-        decoder.serialize(&values)?;
-        let batch = match decoder.flush()? {
-            Some(batch) => batch,
-            None => {
-                return exec_err!("failed to deserialize mcp-server({}) response", tool_name);
-            }
-        };
-
-        let mem = MemTable::try_new(schema, vec![vec![batch]])?;
-        Ok(Arc::new(mem))
-    }
 }
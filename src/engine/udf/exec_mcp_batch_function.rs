@@ -0,0 +1,281 @@
+use crate::config::mcp_server_config::McpServerConfig;
+use crate::engine::json_table::array_to_values;
+use crate::engine::mcp_runtime::McpRuntime;
+use crate::engine::mcp_tool_caller::McpToolCaller;
+use crate::engine::query_state::QueryState;
+use crate::engine::udf::async_mcp_function::{
+    AsyncMcpFunction, MAX_CONCURRENT_CALLS, invoke_blocking,
+};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use datafusion::arrow::array::{Array, ArrayRef, AsArray, StringArray};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::{Result, ScalarValue, exec_datafusion_err, exec_err, plan_err};
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_expr::PhysicalExpr;
+use datafusion::physical_expr::expressions::Literal;
+use futures::{StreamExt, TryStreamExt};
+use rmcp::model::JsonObject;
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+
+pub const EXEC_MCP_BATCH_FUNCTION_NAME: &str = "exec_mcp_batch";
+
+const EXEC_MCP_BATCH_OPTIONS: &[&str] = &["batch_arg", "batch_size"];
+
+const DEFAULT_BATCH_SIZE: usize = 50;
+
+/// Same as exec_mcp, but passes the arguments of up to `batch_size` rows to a single call as an
+/// array, and takes the returned array as the responses of the rows.
+/// e.g. `exec_mcp_batch('llm', 'classify', {'text': body}, batch_arg => 'items', batch_size => 20)`
+///
+/// The arguments of a row are any value. Rows of NULL arguments are not sent and get NULL.
+#[derive(Debug)]
+pub struct ExecMcpBatchFunction {
+    servers: DashMap<String, Arc<McpServerConfig>>,
+    runtime: Arc<McpRuntime>,
+    query_state: Arc<QueryState>,
+    signature: Signature,
+}
+
+impl ExecMcpBatchFunction {
+    pub fn new(servers: DashMap<String, Arc<McpServerConfig>>, runtime: Arc<McpRuntime>) -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![TypeSignature::Any(3), TypeSignature::Any(4)],
+                Volatility::Volatile,
+            ),
+            servers,
+            runtime,
+            query_state: Arc::default(),
+        }
+    }
+
+    /// The same function, making the calls for the statement of `query_state`.
+    pub fn with_query_state(&self, query_state: Arc<QueryState>) -> Self {
+        Self {
+            servers: self.servers.clone(),
+            runtime: self.runtime.clone(),
+            query_state,
+            signature: self.signature.clone(),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ExecMcpBatchFunction {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        EXEC_MCP_BATCH_FUNCTION_NAME
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if arg_types[0] != DataType::Utf8 || arg_types[1] != DataType::Utf8 {
+            return plan_err!(
+                "server and tool for {} must be strings",
+                EXEC_MCP_BATCH_FUNCTION_NAME
+            );
+        }
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let responses = invoke_blocking(
+            EXEC_MCP_BATCH_FUNCTION_NAME,
+            self.invoke_async(&args.args, args.number_rows),
+        )?;
+
+        Ok(ColumnarValue::Array(responses))
+    }
+}
+
+/// Rows sent by a call.
+struct Batch {
+    server_name: String,
+    tool_name: String,
+    rows: Vec<usize>,
+    items: Vec<Value>,
+}
+
+#[async_trait]
+impl AsyncMcpFunction for ExecMcpBatchFunction {
+    async fn invoke_async(&self, args: &[ColumnarValue], number_rows: usize) -> Result<ArrayRef> {
+        let (batch_arg, batch_size) = Self::extract_options(args.get(3))?;
+
+        let server_names = args[0].to_array(number_rows)?;
+        let tool_names = args[1].to_array(number_rows)?;
+        let items = array_to_values(&args[2].to_array(number_rows)?)?;
+
+        let server_names = server_names.as_string::<i32>();
+        let tool_names = tool_names.as_string::<i32>();
+        let mut batches: Vec<Batch> = vec![];
+        for (row, item) in items.into_iter().enumerate() {
+            let Some(item) = item else {
+                continue;
+            };
+            let server_name = server_names.is_valid(row).then(|| server_names.value(row));
+            let tool_name = tool_names.is_valid(row).then(|| tool_names.value(row));
+            let (server_name, tool_name) = (
+                server_name.unwrap_or_default(),
+                tool_name.unwrap_or_default(),
+            );
+
+            match batches.last_mut() {
+                Some(batch)
+                    if batch.server_name == server_name
+                        && batch.tool_name == tool_name
+                        && batch.rows.len() < batch_size =>
+                {
+                    batch.rows.push(row);
+                    batch.items.push(item);
+                }
+                _ => batches.push(Batch {
+                    server_name: server_name.to_string(),
+                    tool_name: tool_name.to_string(),
+                    rows: vec![row],
+                    items: vec![item],
+                }),
+            }
+        }
+
+        let results: Vec<(Vec<usize>, Vec<String>)> = futures::stream::iter(batches)
+            .map(|batch| self.call(batch, &batch_arg))
+            .buffered(MAX_CONCURRENT_CALLS)
+            .try_collect()
+            .await?;
+
+        let mut responses: Vec<Option<String>> = vec![None; number_rows];
+        for (rows, texts) in results {
+            for (row, text) in rows.into_iter().zip(texts) {
+                responses[row] = Some(text);
+            }
+        }
+
+        Ok(Arc::new(StringArray::from(responses)))
+    }
+
+    fn estimate_calls(&self, args: &[Arc<dyn PhysicalExpr>], number_rows: usize) -> usize {
+        let options = args
+            .get(3)
+            .and_then(|arg| arg.as_any().downcast_ref::<Literal>())
+            .map(|literal| ColumnarValue::Scalar(literal.value().clone()));
+        match Self::extract_options(options.as_ref()) {
+            Ok((_, batch_size)) => number_rows.div_ceil(batch_size),
+            Err(_) => number_rows,
+        }
+    }
+}
+
+impl ExecMcpBatchFunction {
+    async fn call(&self, batch: Batch, batch_arg: &str) -> Result<(Vec<usize>, Vec<String>)> {
+        let Some(config) = self.servers.get(&batch.server_name).map(|c| c.clone()) else {
+            return plan_err!(
+                "not registered mcp server is used for {}. Server: {}",
+                EXEC_MCP_BATCH_FUNCTION_NAME,
+                batch.server_name
+            );
+        };
+        if !config.is_tool_allowed(&batch.tool_name) {
+            return plan_err!(
+                "not allowed tool is used for {}. Server: {}, Tool: {}",
+                EXEC_MCP_BATCH_FUNCTION_NAME,
+                batch.server_name,
+                batch.tool_name
+            );
+        }
+
+        let item_count = batch.items.len();
+        let mut arguments = JsonObject::new();
+        arguments.insert(batch_arg.to_string(), Value::Array(batch.items));
+        let text = McpToolCaller::new(config, self.runtime.clone(), self.query_state.clone())
+            .call_checked(batch.tool_name.clone(), Some(arguments))
+            .await?;
+
+        let results = match serde_json::from_str::<Value>(&text) {
+            Ok(Value::Array(results)) => results,
+            _ => {
+                return exec_err!(
+                    "tool({}) of mcp-server({}) must return an array for {}. response: {}",
+                    batch.tool_name,
+                    batch.server_name,
+                    EXEC_MCP_BATCH_FUNCTION_NAME,
+                    text
+                );
+            }
+        };
+        if results.len() != item_count {
+            return exec_err!(
+                "tool({}) of mcp-server({}) returned {} results for {} items of {}. It must return one per item, in the same order",
+                batch.tool_name,
+                batch.server_name,
+                results.len(),
+                item_count,
+                batch_arg
+            );
+        }
+
+        let texts = results
+            .into_iter()
+            .map(|result| match result {
+                Value::String(text) => text,
+                result => result.to_string(),
+            })
+            .collect();
+        Ok((batch.rows, texts))
+    }
+
+    /// `batch_arg` and `batch_size`, given as named arguments.
+    fn extract_options(options: Option<&ColumnarValue>) -> Result<(String, usize)> {
+        let Some(ColumnarValue::Scalar(ScalarValue::Struct(options))) = options else {
+            return plan_err!(
+                "{} takes the name of the array argument. e.g. {}('server', 'tool', {{'text': body}}, batch_arg => 'items')",
+                EXEC_MCP_BATCH_FUNCTION_NAME,
+                EXEC_MCP_BATCH_FUNCTION_NAME
+            );
+        };
+
+        let mut batch_arg = None;
+        let mut batch_size = DEFAULT_BATCH_SIZE;
+        for (field, column) in options.fields().iter().zip(options.columns()) {
+            let value = ScalarValue::try_from_array(column, 0)?;
+            match field.name().as_str() {
+                "batch_arg" => batch_arg = Some(value.to_string()),
+                "batch_size" => {
+                    batch_size = value
+                        .to_string()
+                        .parse()
+                        .ok()
+                        .filter(|size| *size > 0)
+                        .ok_or_else(|| {
+                            exec_datafusion_err!(
+                                "batch_size must be a positive number. input: {}",
+                                value
+                            )
+                        })?;
+                }
+                key => {
+                    return plan_err!(
+                        "unknown option for {}. Option: {}, Supported: [{}]",
+                        EXEC_MCP_BATCH_FUNCTION_NAME,
+                        key,
+                        EXEC_MCP_BATCH_OPTIONS.join(", ")
+                    );
+                }
+            }
+        }
+
+        match batch_arg {
+            Some(batch_arg) if !batch_arg.is_empty() => Ok((batch_arg, batch_size)),
+            _ => plan_err!("batch_arg is required for {}", EXEC_MCP_BATCH_FUNCTION_NAME),
+        }
+    }
+}
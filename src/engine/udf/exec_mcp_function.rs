@@ -1,8 +1,14 @@
 use crate::config::mcp_server_config::McpServerConfig;
+use crate::engine::mcp_runtime::McpRuntime;
 use crate::engine::mcp_tool_caller::McpToolCaller;
+use crate::engine::query_state::QueryState;
+use crate::engine::udf::async_mcp_function::{
+    AsyncMcpFunction, MAX_CONCURRENT_CALLS, invoke_blocking,
+};
+use async_trait::async_trait;
 use dashmap::DashMap;
 use datafusion::arrow::array::{
-    Array, AsArray, BooleanArray, Float64Array, Int64Array, StringArray, StructArray,
+    Array, ArrayRef, AsArray, BooleanArray, Float64Array, Int64Array, StringArray, StructArray,
 };
 use datafusion::arrow::datatypes::DataType;
 use datafusion::common::Result;
@@ -10,22 +16,28 @@ use datafusion::common::plan_err;
 use datafusion::logical_expr::{
     ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, TypeSignature, Volatility,
 };
+use futures::{StreamExt, TryStreamExt};
 use rmcp::model::JsonObject;
 use serde_json::{Map, Value};
 use std::any::Any;
 use std::sync::Arc;
 use std::vec::Vec as StdVec;
 
-const EXEC_MCP_FUNCTION_NAME: &str = "exec_mcp";
+pub const EXEC_MCP_FUNCTION_NAME: &str = "exec_mcp";
+
+/// Server, tool and arguments of a call.
+type McpCallArguments = (String, String, Option<JsonObject>);
 
 #[derive(Debug)]
 pub struct ExecMcpFunction {
     servers: DashMap<String, Arc<McpServerConfig>>,
+    runtime: Arc<McpRuntime>,
+    query_state: Arc<QueryState>,
     signature: Signature,
 }
 
 impl ExecMcpFunction {
-    pub fn new(servers: DashMap<String, Arc<McpServerConfig>>) -> Self {
+    pub fn new(servers: DashMap<String, Arc<McpServerConfig>>, runtime: Arc<McpRuntime>) -> Self {
         Self {
             signature: Signature::one_of(
                 vec![
@@ -33,9 +45,22 @@ impl ExecMcpFunction {
                     // for vec![Utf8, Utf8, Struct]
                     TypeSignature::Any(3),
                 ],
-                Volatility::Stable,
+                // Not folded into a constant while planning, so the tools are called on execution.
+                Volatility::Volatile,
             ),
             servers,
+            runtime,
+            query_state: Arc::default(),
+        }
+    }
+
+    /// The same function, making the calls for the statement of `query_state`.
+    pub fn with_query_state(&self, query_state: Arc<QueryState>) -> Self {
+        Self {
+            servers: self.servers.clone(),
+            runtime: self.runtime.clone(),
+            query_state,
+            signature: self.signature.clone(),
         }
     }
 }
@@ -58,36 +83,73 @@ impl ScalarUDFImpl for ExecMcpFunction {
     }
 
     fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
-        let mcp_args = self.validate_args(&args)?;
+        let responses = invoke_blocking(
+            EXEC_MCP_FUNCTION_NAME,
+            self.invoke_async(&args.args, args.number_rows),
+        )?;
 
-        let mut responses = Vec::<String>::new();
-        for (server_name, tool_name, tool_arguments) in mcp_args {
-            let Some(config) = self.servers.get(server_name.as_str()) else {
-                return plan_err!(
-                    "not registered mcp server is used for {}. Server: {}",
-                    EXEC_MCP_FUNCTION_NAME,
-                    server_name
-                );
-            };
-
-            let tool_caller = McpToolCaller::new(config.clone());
-            let response = tool_caller.call_as_string(tool_name.clone(), tool_arguments)?;
-            responses.push(response);
-        }
+        Ok(ColumnarValue::Array(responses))
+    }
+}
 
-        Ok(ColumnarValue::Array(Arc::new(StringArray::from(responses))))
+#[async_trait]
+impl AsyncMcpFunction for ExecMcpFunction {
+    /// Calls the tools, some at a time, keeping the order of the rows.
+    async fn invoke_async(&self, args: &[ColumnarValue], number_rows: usize) -> Result<ArrayRef> {
+        let calls = self.calls(args, number_rows)?;
+        let responses: Vec<String> = futures::stream::iter(calls)
+            .map(|(server_name, tool_name, tool_arguments)| {
+                self.call(server_name, tool_name, tool_arguments)
+            })
+            .buffered(MAX_CONCURRENT_CALLS)
+            .try_collect()
+            .await?;
+
+        Ok(Arc::new(StringArray::from(responses)))
     }
 }
 
 impl ExecMcpFunction {
-    fn validate_args(
+    async fn call(
+        &self,
+        server_name: String,
+        tool_name: String,
+        tool_arguments: Option<JsonObject>,
+    ) -> Result<String> {
+        let Some(config) = self.servers.get(server_name.as_str()).map(|c| c.clone()) else {
+            return plan_err!(
+                "not registered mcp server is used for {}. Server: {}",
+                EXEC_MCP_FUNCTION_NAME,
+                server_name
+            );
+        };
+
+        if !config.is_tool_allowed(tool_name.as_str()) {
+            return plan_err!(
+                "not allowed tool is used for {}. Server: {}, Tool: {}",
+                EXEC_MCP_FUNCTION_NAME,
+                server_name,
+                tool_name
+            );
+        }
+
+        McpToolCaller::new(config, self.runtime.clone(), self.query_state.clone())
+            .call_as_string(tool_name, tool_arguments)
+            .await
+    }
+
+    /// Server, tool and arguments of each row.
+    fn calls(
         &self,
-        args: &ScalarFunctionArgs,
-    ) -> Result<Vec<(String, String, Option<JsonObject>)>> {
-        let arguments = &args.args;
+        arguments: &[ColumnarValue],
+        number_rows: usize,
+    ) -> Result<Vec<McpCallArguments>> {
         self.check_arg_types(arguments)?;
 
-        let args = ColumnarValue::values_to_arrays(arguments)?;
+        let args = arguments
+            .iter()
+            .map(|arg| arg.to_array(number_rows))
+            .collect::<Result<Vec<_>>>()?;
 
         let server_names = args[0].as_string::<i32>();
         let tool_names = args[1].as_string::<i32>();
@@ -96,7 +158,7 @@ impl ExecMcpFunction {
             None => None,
         };
 
-        let mut results = StdVec::<(String, String, Option<JsonObject>)>::new();
+        let mut results = StdVec::<McpCallArguments>::new();
 
         let mut server_name_iter = server_names.iter();
         let mut tool_name_iter = tool_names.iter();
@@ -117,7 +179,7 @@ impl ExecMcpFunction {
         Ok(results)
     }
 
-    fn check_arg_types(&self, arguments: &Vec<ColumnarValue>) -> Result<()> {
+    fn check_arg_types(&self, arguments: &[ColumnarValue]) -> Result<()> {
         let first_arg = match arguments.get(0) {
             Some(v) => v,
             None => {
@@ -0,0 +1,52 @@
+use datafusion::common::{Result, ScalarValue, plan_err};
+use datafusion::logical_expr::Expr;
+use std::collections::HashMap;
+
+/// Extracts options given as named arguments or a struct. e.g. `{'header': false}`
+pub(super) fn extract_options(
+    function_name: &str,
+    expr: Option<&Expr>,
+    supported: &[&str],
+) -> Result<HashMap<String, String>> {
+    let f = match expr {
+        None => return Ok(HashMap::new()),
+        Some(Expr::ScalarFunction(f)) if f.func.name() == "named_struct" => f,
+        _ => {
+            return plan_err!(
+                "options for {} must be named arguments or a named_struct. e.g. {}(path, option => 'value'). input: {:?}",
+                function_name,
+                function_name,
+                expr
+            );
+        }
+    };
+
+    let mut options = HashMap::new();
+    for pair in f.args.chunks(2) {
+        let (Expr::Literal(ScalarValue::Utf8(Some(key))), Some(Expr::Literal(value))) =
+            (&pair[0], pair.get(1))
+        else {
+            return plan_err!(
+                "option value for {} must be a literal. input: {:?}",
+                function_name,
+                pair
+            );
+        };
+
+        if !supported.contains(&key.as_str()) {
+            return plan_err!(
+                "unknown option for {}. Option: {}, Supported: [{}]",
+                function_name,
+                key,
+                supported.join(", ")
+            );
+        }
+        if value.is_null() {
+            continue;
+        }
+
+        options.insert(key.clone(), value.to_string());
+    }
+
+    Ok(options)
+}
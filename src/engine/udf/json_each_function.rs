@@ -0,0 +1,280 @@
+use crate::engine::bind_query_state_rule::BindQueryStateRule;
+use crate::engine::query_state::QueryStateExtension;
+use crate::engine::udf::json_path::{PathElement, lookup, parse_json_path};
+use async_trait::async_trait;
+use datafusion::arrow::array::{ArrayRef, RecordBatch, RecordBatchOptions, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::catalog::{Session, TableFunctionImpl, TableProvider};
+use datafusion::common::{
+    DFSchema, Result, ScalarValue, exec_datafusion_err, exec_err, internal_err, plan_err,
+};
+use datafusion::datasource::TableType;
+use datafusion::execution::context::ExecutionProps;
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::{ColumnarValue, Expr};
+use datafusion::physical_expr::{EquivalenceProperties, Partitioning, create_physical_expr};
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use serde_json::Value;
+use std::any::Any;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+pub const JSON_EACH_FUNCTION_NAME: &str = "json_each";
+
+/// Returns the elements of a JSON array or the members of an object as rows of `key`, `value` (JSON
+/// text) and `type`. The JSON can be an expression such as `exec_mcp(...)`, evaluated once when
+/// the query is executed.
+/// e.g. `SELECT * FROM json_each(exec_mcp('server', 'tool'), '$.items')`
+#[derive(Debug, Default)]
+pub struct JsonEachFunction {}
+
+impl TableFunctionImpl for JsonEachFunction {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        if args.is_empty() || args.len() > 2 {
+            return plan_err!(
+                "{} takes a JSON string and an optional path. e.g. {}('[1, 2]')",
+                JSON_EACH_FUNCTION_NAME,
+                JSON_EACH_FUNCTION_NAME
+            );
+        }
+
+        let path = match args.get(1).map(Self::evaluate).transpose()? {
+            None => vec![],
+            Some(ScalarValue::Utf8(Some(path))) => parse_json_path(&path).ok_or_else(|| {
+                exec_datafusion_err!("invalid JSON path: {}. e.g. '$.items[0].name'", path)
+            })?,
+            Some(value) => {
+                return plan_err!(
+                    "second argument for {} must be a JSON path. input: {}",
+                    JSON_EACH_FUNCTION_NAME,
+                    value
+                );
+            }
+        };
+
+        Ok(Arc::new(JsonEachTable {
+            json: args[0].clone(),
+            path,
+        }))
+    }
+}
+
+impl JsonEachFunction {
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("key", DataType::Utf8, true),
+            Field::new("value", DataType::Utf8, false),
+            Field::new("type", DataType::Utf8, false),
+        ]))
+    }
+
+    /// Evaluates the JSON and returns its entries at the path.
+    fn entries(json: &Expr, path: &[PathElement]) -> Result<RecordBatch> {
+        let json = match Self::evaluate(json)? {
+            ScalarValue::Utf8(json)
+            | ScalarValue::LargeUtf8(json)
+            | ScalarValue::Utf8View(json) => json,
+            ScalarValue::Null => None,
+            value => {
+                return exec_err!(
+                    "first argument for {} must be a JSON string. input: {}",
+                    JSON_EACH_FUNCTION_NAME,
+                    value
+                );
+            }
+        };
+
+        let value = match json {
+            Some(json) => serde_json::from_str::<Value>(&json).map_err(|e| {
+                exec_datafusion_err!(
+                    "failed to parse JSON for {}. {}",
+                    JSON_EACH_FUNCTION_NAME,
+                    e
+                )
+            })?,
+            None => Value::Null,
+        };
+        let entries: Vec<(Option<String>, &Value)> = match lookup(&value, path) {
+            Some(Value::Object(object)) => {
+                object.iter().map(|(k, v)| (Some(k.clone()), v)).collect()
+            }
+            Some(Value::Array(array)) => array
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (Some(i.to_string()), v))
+                .collect(),
+            Some(Value::Null) | None => vec![],
+            Some(value) => vec![(None, value)],
+        };
+
+        let keys: Vec<_> = entries.iter().map(|(k, _)| k.clone()).collect();
+        let values: Vec<_> = entries.iter().map(|(_, v)| v.to_string()).collect();
+        let types: Vec<_> = entries.iter().map(|(_, v)| Self::type_name(v)).collect();
+
+        Ok(RecordBatch::try_new(
+            Self::schema(),
+            vec![
+                Arc::new(StringArray::from(keys)) as ArrayRef,
+                Arc::new(StringArray::from(values)),
+                Arc::new(StringArray::from(types)),
+            ],
+        )?)
+    }
+
+    /// Evaluates the constant argument. Table functions take no columns.
+    fn evaluate(expr: &Expr) -> Result<ScalarValue> {
+        if let Expr::Literal(value) = expr {
+            return Ok(value.clone());
+        }
+
+        let physical_expr = create_physical_expr(expr, &DFSchema::empty(), &ExecutionProps::new())?;
+        let batch = RecordBatch::try_new_with_options(
+            Arc::new(Schema::empty()),
+            vec![],
+            &RecordBatchOptions::new().with_row_count(Some(1)),
+        )?;
+        match physical_expr.evaluate(&batch)? {
+            ColumnarValue::Scalar(value) => Ok(value),
+            ColumnarValue::Array(array) => ScalarValue::try_from_array(&array, 0),
+        }
+    }
+
+    fn type_name(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+}
+
+/// The result of json_each. The JSON isn't evaluated until the plan is executed, so the tools
+/// in it are called by the execution, not by planning or EXPLAIN.
+#[derive(Debug)]
+struct JsonEachTable {
+    json: Expr,
+    path: Vec<PathElement>,
+}
+
+#[async_trait]
+impl TableProvider for JsonEachTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        JsonEachFunction::schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(JsonEachExecutionPlan::try_new(
+            self.json.clone(),
+            self.path.clone(),
+            projection.cloned(),
+        )?))
+    }
+}
+
+#[derive(Debug)]
+struct JsonEachExecutionPlan {
+    json: Expr,
+    path: Vec<PathElement>,
+    projection: Option<Vec<usize>>,
+    plan_properties: PlanProperties,
+}
+
+impl JsonEachExecutionPlan {
+    fn try_new(json: Expr, path: Vec<PathElement>, projection: Option<Vec<usize>>) -> Result<Self> {
+        let schema = match &projection {
+            Some(projection) => Arc::new(JsonEachFunction::schema().project(projection)?),
+            None => JsonEachFunction::schema(),
+        };
+        let plan_properties = PlanProperties::new(
+            EquivalenceProperties::new(schema),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        );
+
+        Ok(Self {
+            json,
+            path,
+            projection,
+            plan_properties,
+        })
+    }
+}
+
+impl DisplayAs for JsonEachExecutionPlan {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "JsonEachExecutionPlan: json={}", self.json)
+    }
+}
+
+impl ExecutionPlan for JsonEachExecutionPlan {
+    fn name(&self) -> &str {
+        "JsonEachExecutionPlan"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !children.is_empty() {
+            return internal_err!("JsonEachExecutionPlan takes no children");
+        }
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        _partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        // Table function arguments aren't analyzed, so the calls are bound to the statement here.
+        let query_state = QueryStateExtension::query_state(context.session_config().options());
+        let json = BindQueryStateRule::bind_expr(self.json.clone(), &query_state)?;
+        let path = self.path.clone();
+        let projection = self.projection.clone();
+
+        let stream = futures::stream::once(async move {
+            let batch = JsonEachFunction::entries(&json, &path)?;
+            match projection {
+                Some(projection) => Ok(batch.project(&projection)?),
+                None => Ok(batch),
+            }
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+}
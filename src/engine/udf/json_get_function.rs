@@ -0,0 +1,115 @@
+use crate::engine::udf::json_path::{PathStyle, map_json_rows};
+use datafusion::arrow::array::{ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::Result;
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+use std::any::Any;
+use std::sync::Arc;
+
+pub const JSON_GET_STR_FUNCTION_NAME: &str = "json_get_str";
+pub const JSON_GET_INT_FUNCTION_NAME: &str = "json_get_int";
+pub const JSON_GET_FLOAT_FUNCTION_NAME: &str = "json_get_float";
+pub const JSON_GET_BOOL_FUNCTION_NAME: &str = "json_get_bool";
+
+#[derive(Debug, Clone, Copy)]
+pub enum JsonGetType {
+    Str,
+    Int,
+    Float,
+    Bool,
+}
+
+impl JsonGetType {
+    fn function_name(&self) -> &'static str {
+        match self {
+            JsonGetType::Str => JSON_GET_STR_FUNCTION_NAME,
+            JsonGetType::Int => JSON_GET_INT_FUNCTION_NAME,
+            JsonGetType::Float => JSON_GET_FLOAT_FUNCTION_NAME,
+            JsonGetType::Bool => JSON_GET_BOOL_FUNCTION_NAME,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            JsonGetType::Str => DataType::Utf8,
+            JsonGetType::Int => DataType::Int64,
+            JsonGetType::Float => DataType::Float64,
+            JsonGetType::Bool => DataType::Boolean,
+        }
+    }
+}
+
+/// Gets the value of the type from a JSON string by keys and indexes. Null if the value is missing
+/// or of another type. e.g. `json_get_str(response, 'items', 0, 'name')`
+#[derive(Debug)]
+pub struct JsonGetFunction {
+    get_type: JsonGetType,
+    signature: Signature,
+}
+
+impl JsonGetFunction {
+    pub fn new(get_type: JsonGetType) -> Self {
+        Self {
+            get_type,
+            signature: Signature::variadic_any(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonGetFunction {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.get_type.function_name()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(self.get_type.data_type())
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let name = self.name();
+        let (args, rows, style) = (&args.args, args.number_rows, PathStyle::Keys);
+
+        let array: ArrayRef = match self.get_type {
+            JsonGetType::Str => Arc::new(StringArray::from(map_json_rows(
+                name,
+                args,
+                rows,
+                style,
+                |v| v.as_str().map(String::from),
+            )?)),
+            JsonGetType::Int => Arc::new(Int64Array::from(map_json_rows(
+                name,
+                args,
+                rows,
+                style,
+                |v| v.as_i64(),
+            )?)),
+            JsonGetType::Float => Arc::new(Float64Array::from(map_json_rows(
+                name,
+                args,
+                rows,
+                style,
+                |v| v.as_f64(),
+            )?)),
+            JsonGetType::Bool => Arc::new(BooleanArray::from(map_json_rows(
+                name,
+                args,
+                rows,
+                style,
+                |v| v.as_bool(),
+            )?)),
+        };
+
+        Ok(ColumnarValue::Array(array))
+    }
+}
@@ -0,0 +1,253 @@
+use datafusion::arrow::array::{Array, ArrayRef, AsArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Int64Type};
+use datafusion::common::{Result, exec_err, plan_err};
+use datafusion::logical_expr::ColumnarValue;
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum PathElement {
+    Key(String),
+    Index(usize),
+}
+
+/// How the arguments after the JSON select the value.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum PathStyle {
+    /// Keys and indexes as arguments. e.g. `json_get_str(json, 'user', 'login')`
+    Keys,
+    /// A JSONPath argument. e.g. `json_extract(json, '$.user.login')`
+    JsonPath,
+}
+
+/// Parses a JSONPath such as `$.items[0].name` or `$["a key"]`. `$` can be omitted.
+pub(super) fn parse_json_path(path: &str) -> Option<Vec<PathElement>> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let chars: Vec<char> = path.chars().collect();
+
+    let mut elements = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '[' => {
+                let (element, end) = parse_bracket(&chars, i + 1)?;
+                elements.push(element);
+                i = end + 1;
+            }
+            c => {
+                // The first key can omit the dot. e.g. 'user.login'
+                if c == '.' {
+                    i += 1;
+                } else if !elements.is_empty() {
+                    return None;
+                }
+                let end = chars[i..]
+                    .iter()
+                    .position(|c| matches!(c, '.' | '[' | ']'))
+                    .map_or(chars.len(), |p| i + p);
+                if end == i {
+                    return None;
+                }
+                elements.push(PathElement::Key(chars[i..end].iter().collect()));
+                i = end;
+            }
+        }
+    }
+
+    Some(elements)
+}
+
+/// Parses the inside of `[...]` from `start`, returning the element and the position of `]`.
+/// A quoted key can hold `]`, `.` and its quote escaped by a backslash. e.g. `["a]b"]`, `['it\'s']`
+fn parse_bracket(chars: &[char], start: usize) -> Option<(PathElement, usize)> {
+    let mut i = start;
+    while chars.get(i)?.is_whitespace() {
+        i += 1;
+    }
+
+    let quote = chars[i];
+    if quote != '"' && quote != '\'' {
+        let end = i + chars[i..].iter().position(|c| *c == ']')?;
+        let index: String = chars[i..end].iter().collect();
+        return Some((PathElement::Index(index.trim().parse().ok()?), end));
+    }
+
+    let mut key = String::new();
+    i += 1;
+    loop {
+        match *chars.get(i)? {
+            '\\' => {
+                key.push(*chars.get(i + 1)?);
+                i += 2;
+            }
+            c if c == quote => break,
+            c => {
+                key.push(c);
+                i += 1;
+            }
+        }
+    }
+    i += 1;
+    while chars.get(i)?.is_whitespace() {
+        i += 1;
+    }
+
+    (chars[i] == ']').then_some((PathElement::Key(key), i))
+}
+
+pub(super) fn lookup<'a>(value: &'a Value, path: &[PathElement]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, element| match element {
+        PathElement::Key(key) => value.as_object()?.get(key),
+        PathElement::Index(index) => value.as_array()?.get(*index),
+    })
+}
+
+/// Applies `f` to the value selected in the JSON of each row. Invalid JSON and missing values are
+/// null.
+pub(super) fn map_json_rows<T>(
+    function_name: &str,
+    args: &[ColumnarValue],
+    number_rows: usize,
+    style: PathStyle,
+    f: impl Fn(&Value) -> Option<T>,
+) -> Result<Vec<Option<T>>> {
+    let Some(json_arg) = args.first() else {
+        return plan_err!(
+            "{} takes a JSON string as the first argument",
+            function_name
+        );
+    };
+    if matches!(style, PathStyle::JsonPath) && args.len() > 2 {
+        return plan_err!(
+            "{} takes a JSON string and a path. e.g. {}(json, '$.items[0]')",
+            function_name,
+            function_name
+        );
+    }
+
+    let json_array = cast(&json_arg.to_array(number_rows)?, &DataType::Utf8)?;
+    let json_array = json_array.as_string::<i32>();
+    let path_arrays = args[1..]
+        .iter()
+        .map(|arg| {
+            let array = arg.to_array(number_rows)?;
+            let data_type = if array.data_type().is_integer() {
+                DataType::Int64
+            } else {
+                DataType::Utf8
+            };
+            Ok(cast(&array, &data_type)?)
+        })
+        .collect::<Result<Vec<ArrayRef>>>()?;
+
+    let mut results = Vec::with_capacity(number_rows);
+    for row in 0..number_rows {
+        let Some(path) = row_path(&path_arrays, row, style)? else {
+            results.push(None);
+            continue;
+        };
+        let result = json_array
+            .is_valid(row)
+            .then(|| serde_json::from_str::<Value>(json_array.value(row)).ok())
+            .flatten()
+            .and_then(|value| lookup(&value, &path).and_then(&f));
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+/// Returns the path of the row, None for null or negative indexes which select nothing.
+fn row_path(
+    path_arrays: &[ArrayRef],
+    row: usize,
+    style: PathStyle,
+) -> Result<Option<Vec<PathElement>>> {
+    let mut path = Vec::new();
+    for array in path_arrays {
+        if array.is_null(row) {
+            return Ok(None);
+        }
+        match (array.data_type(), style) {
+            (DataType::Int64, _) => {
+                let index = array.as_primitive::<Int64Type>().value(row);
+                let Ok(index) = usize::try_from(index) else {
+                    return Ok(None);
+                };
+                path.push(PathElement::Index(index));
+            }
+            (_, PathStyle::Keys) => path.push(PathElement::Key(
+                array.as_string::<i32>().value(row).to_string(),
+            )),
+            (_, PathStyle::JsonPath) => {
+                let json_path = array.as_string::<i32>().value(row);
+                let Some(elements) = parse_json_path(json_path) else {
+                    return exec_err!("invalid JSON path: {}. e.g. '$.items[0].name'", json_path);
+                };
+                path.extend(elements);
+            }
+        }
+    }
+
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: &str) -> PathElement {
+        PathElement::Key(key.to_string())
+    }
+
+    #[test]
+    fn parse_json_path_reads_keys_and_indexes() {
+        assert_eq!(parse_json_path("$"), Some(vec![]));
+        assert_eq!(
+            parse_json_path("$.items[0].name"),
+            Some(vec![key("items"), PathElement::Index(0), key("name")])
+        );
+        assert_eq!(
+            parse_json_path("user.login"),
+            Some(vec![key("user"), key("login")])
+        );
+        assert_eq!(
+            parse_json_path("$[ 2 ][10]"),
+            Some(vec![PathElement::Index(2), PathElement::Index(10)])
+        );
+    }
+
+    #[test]
+    fn parse_json_path_reads_quoted_keys() {
+        assert_eq!(parse_json_path(r#"$["a key"]"#), Some(vec![key("a key")]));
+        assert_eq!(parse_json_path("$['a.b']"), Some(vec![key("a.b")]));
+        assert_eq!(
+            parse_json_path(r#"$["a]b"].c"#),
+            Some(vec![key("a]b"), key("c")])
+        );
+        assert_eq!(parse_json_path(r"$['it\'s']"), Some(vec![key("it's")]));
+        assert_eq!(
+            parse_json_path(r#"$[ "x" ][0]"#),
+            Some(vec![key("x"), PathElement::Index(0)])
+        );
+    }
+
+    #[test]
+    fn parse_json_path_rejects_invalid_paths() {
+        for path in [
+            "$.",
+            "$..a",
+            "$[",
+            "$[]",
+            "$[a]",
+            "$[-1]",
+            r#"$["a"#,
+            r#"$["a"x]"#,
+            "$.a b[",
+            "a]",
+        ] {
+            assert_eq!(parse_json_path(path), None, "{}", path);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::engine::udf::json_path::{PathStyle, map_json_rows};
+use datafusion::arrow::array::{ArrayRef, Int64Array, ListBuilder, StringArray, StringBuilder};
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::Result;
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+use std::any::Any;
+use std::sync::Arc;
+
+pub const JSON_EXTRACT_FUNCTION_NAME: &str = "json_extract";
+pub const JSON_ARRAY_LENGTH_FUNCTION_NAME: &str = "json_array_length";
+pub const JSON_KEYS_FUNCTION_NAME: &str = "json_keys";
+
+#[derive(Debug, Clone, Copy)]
+pub enum JsonQuery {
+    /// The value as JSON text.
+    Extract,
+    /// The length of the array.
+    ArrayLength,
+    /// The keys of the object.
+    Keys,
+}
+
+impl JsonQuery {
+    fn function_name(&self) -> &'static str {
+        match self {
+            JsonQuery::Extract => JSON_EXTRACT_FUNCTION_NAME,
+            JsonQuery::ArrayLength => JSON_ARRAY_LENGTH_FUNCTION_NAME,
+            JsonQuery::Keys => JSON_KEYS_FUNCTION_NAME,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            JsonQuery::Extract => DataType::Utf8,
+            JsonQuery::ArrayLength => DataType::Int64,
+            JsonQuery::Keys => {
+                DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+            }
+        }
+    }
+}
+
+/// Queries a JSON string at the optional JSONPath. e.g. `json_extract(response, '$.items[0]')`
+#[derive(Debug)]
+pub struct JsonQueryFunction {
+    query: JsonQuery,
+    signature: Signature,
+}
+
+impl JsonQueryFunction {
+    pub fn new(query: JsonQuery) -> Self {
+        Self {
+            query,
+            signature: Signature::variadic_any(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonQueryFunction {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.query.function_name()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(self.query.data_type())
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let name = self.name();
+        let (args, rows, style) = (&args.args, args.number_rows, PathStyle::JsonPath);
+
+        let array: ArrayRef = match self.query {
+            JsonQuery::Extract => Arc::new(StringArray::from(map_json_rows(
+                name,
+                args,
+                rows,
+                style,
+                |v| Some(v.to_string()),
+            )?)),
+            JsonQuery::ArrayLength => Arc::new(Int64Array::from(map_json_rows(
+                name,
+                args,
+                rows,
+                style,
+                |v| v.as_array().map(|a| a.len() as i64),
+            )?)),
+            JsonQuery::Keys => {
+                let keys = map_json_rows(name, args, rows, style, |v| {
+                    v.as_object()
+                        .map(|o| o.keys().map(|k| Some(k.clone())).collect::<Vec<_>>())
+                })?;
+                let mut builder = ListBuilder::new(StringBuilder::new());
+                for row in keys {
+                    match row {
+                        Some(row) => builder.append_value(row),
+                        None => builder.append_null(),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+        };
+
+        Ok(ColumnarValue::Array(array))
+    }
+}
@@ -0,0 +1,433 @@
+use crate::config::limits_config::LimitsConfig;
+use crate::config::mcp_server_config::McpServerConfig;
+use crate::engine::json_table::array_to_values;
+use crate::engine::mcp_runtime::McpRuntime;
+use crate::engine::mcp_tool_caller::McpToolCaller;
+use crate::engine::query_state::QueryState;
+use crate::engine::udf::async_mcp_function::{MAX_CONCURRENT_CALLS, invoke_blocking};
+use dashmap::DashMap;
+use datafusion::arrow::array::{Array, ArrayRef, AsArray, BooleanArray, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::{Result, ScalarValue, exec_datafusion_err, exec_err, plan_err};
+use datafusion::logical_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion::logical_expr::utils::format_state_name;
+use datafusion::logical_expr::{
+    Accumulator, AggregateUDFImpl, EmitTo, GroupsAccumulator, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_expr::PhysicalExpr;
+use datafusion::physical_expr::expressions::Literal;
+use futures::{StreamExt, TryStreamExt};
+use rmcp::model::JsonObject;
+use serde_json::Value;
+use std::any::Any;
+use std::mem::size_of;
+use std::sync::Arc;
+
+pub const MCP_AGG_FUNCTION_NAME: &str = "mcp_agg";
+
+const MCP_AGG_OPTIONS: &[&str] = &["max_items", "max_bytes"];
+
+const DEFAULT_MAX_ITEMS: usize = 1000;
+const DEFAULT_MAX_BYTES: usize = 1024 * 1024;
+
+const VALUES_PLACEHOLDER: &str = "{values}";
+
+/// Calls the tool once per group with the values of the group.
+/// e.g. `SELECT label, mcp_agg('llm', 'summarize', {'text': 'Summarize:\n{values}'}, title) FROM issues GROUP BY label`
+///
+/// `{values}` in the string arguments of the template is replaced with the values, one per line.
+/// An argument of just `{values}` takes them as a JSON array. A group over `max_items` values or
+/// `max_bytes` of JSON fails the query before calling the tool.
+#[derive(Debug)]
+pub struct McpAggFunction {
+    servers: DashMap<String, Arc<McpServerConfig>>,
+    runtime: Arc<McpRuntime>,
+    query_state: Arc<QueryState>,
+    max_items: usize,
+    max_bytes: usize,
+    signature: Signature,
+}
+
+impl McpAggFunction {
+    pub fn new(
+        servers: DashMap<String, Arc<McpServerConfig>>,
+        runtime: Arc<McpRuntime>,
+        limits: &LimitsConfig,
+    ) -> Self {
+        Self {
+            servers,
+            runtime,
+            query_state: Arc::default(),
+            max_items: limits.mcp_agg_max_items.unwrap_or(DEFAULT_MAX_ITEMS),
+            max_bytes: limits.mcp_agg_max_bytes.unwrap_or(DEFAULT_MAX_BYTES),
+            signature: Signature::one_of(
+                vec![TypeSignature::Any(4), TypeSignature::Any(5)],
+                Volatility::Volatile,
+            ),
+        }
+    }
+
+    /// The same function, making the calls for the statement of `query_state`.
+    pub fn with_query_state(&self, query_state: Arc<QueryState>) -> Self {
+        Self {
+            servers: self.servers.clone(),
+            runtime: self.runtime.clone(),
+            query_state,
+            max_items: self.max_items,
+            max_bytes: self.max_bytes,
+            signature: self.signature.clone(),
+        }
+    }
+}
+
+impl AggregateUDFImpl for McpAggFunction {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        MCP_AGG_FUNCTION_NAME
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(McpAggAccumulator {
+            call: Arc::new(self.create_call(acc_args.exprs)?),
+            group: Group::default(),
+        }))
+    }
+
+    /// The values collected so far, as a JSON array.
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            format_state_name(args.name, "values"),
+            DataType::Utf8,
+            true,
+        )])
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(McpAggGroupsAccumulator {
+            call: Arc::new(self.create_call(args.exprs)?),
+            groups: vec![],
+        }))
+    }
+}
+
+impl McpAggFunction {
+    /// The server, the tool, the template and the options must be literals.
+    fn create_call(&self, exprs: &[Arc<dyn PhysicalExpr>]) -> Result<McpAggCall> {
+        let literal = |index: usize| {
+            exprs
+                .get(index)
+                .and_then(|expr| expr.as_any().downcast_ref::<Literal>())
+                .map(|literal| literal.value().clone())
+        };
+
+        let (Some(ScalarValue::Utf8(Some(server_name))), Some(ScalarValue::Utf8(Some(tool_name)))) =
+            (literal(0), literal(1))
+        else {
+            return plan_err!(
+                "server and tool for {} must be strings",
+                MCP_AGG_FUNCTION_NAME
+            );
+        };
+        let Some(server_config) = self.servers.get(&server_name).map(|c| c.clone()) else {
+            return plan_err!(
+                "not registered mcp server is used for {}. Server: {}",
+                MCP_AGG_FUNCTION_NAME,
+                server_name
+            );
+        };
+        if !server_config.is_tool_allowed(&tool_name) {
+            return plan_err!(
+                "not allowed tool is used for {}. Server: {}, Tool: {}",
+                MCP_AGG_FUNCTION_NAME,
+                server_name,
+                tool_name
+            );
+        }
+
+        let template = match literal(2).map(|t| Self::to_object(&t)).transpose()? {
+            Some(template)
+                if template
+                    .values()
+                    .any(|v| v.as_str().is_some_and(|v| v.contains(VALUES_PLACEHOLDER))) =>
+            {
+                template
+            }
+            _ => {
+                return plan_err!(
+                    "the third argument of {} must be a map of the arguments with {} in a value. e.g. {{'text': 'Summarize:\\n{}'}}",
+                    MCP_AGG_FUNCTION_NAME,
+                    VALUES_PLACEHOLDER,
+                    VALUES_PLACEHOLDER
+                );
+            }
+        };
+
+        let mut call = McpAggCall {
+            server_config,
+            tool_name,
+            template,
+            max_items: self.max_items,
+            max_bytes: self.max_bytes,
+            runtime: self.runtime.clone(),
+            query_state: self.query_state.clone(),
+        };
+        if exprs.len() > 4 {
+            let Some(options) = literal(4) else {
+                return plan_err!("options for {} must be literals", MCP_AGG_FUNCTION_NAME);
+            };
+            for (key, value) in Self::to_object(&options)? {
+                let limit = value.as_u64().map(|v| v as usize).ok_or_else(|| {
+                    exec_datafusion_err!("{} must be a number. input: {}", key, value)
+                })?;
+                match key.as_str() {
+                    "max_items" => call.max_items = limit,
+                    "max_bytes" => call.max_bytes = limit,
+                    _ => {
+                        return plan_err!(
+                            "unknown option for {}. Option: {}, Supported: [{}]",
+                            MCP_AGG_FUNCTION_NAME,
+                            key,
+                            MCP_AGG_OPTIONS.join(", ")
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(call)
+    }
+
+    fn to_object(value: &ScalarValue) -> Result<JsonObject> {
+        match array_to_values(&value.to_array()?)?.pop() {
+            Some(Some(Value::Object(object))) => Ok(object),
+            _ => plan_err!(
+                "expected a map for {}. input: {}",
+                MCP_AGG_FUNCTION_NAME,
+                value
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct McpAggCall {
+    server_config: Arc<McpServerConfig>,
+    tool_name: String,
+    template: JsonObject,
+    max_items: usize,
+    max_bytes: usize,
+    runtime: Arc<McpRuntime>,
+    query_state: Arc<QueryState>,
+}
+
+impl McpAggCall {
+    /// Returns NULL for a group without values, not calling the tool.
+    async fn call(&self, values: Vec<Value>) -> Result<Option<String>> {
+        if values.is_empty() {
+            return Ok(None);
+        }
+
+        let text = values
+            .iter()
+            .map(|value| match value {
+                Value::String(text) => text.clone(),
+                value => value.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let arguments = self
+            .template
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    Value::String(t) if t == VALUES_PLACEHOLDER => Value::Array(values.clone()),
+                    Value::String(t) => Value::String(t.replace(VALUES_PLACEHOLDER, &text)),
+                    value => value.clone(),
+                };
+                (key.clone(), value)
+            })
+            .collect();
+
+        McpToolCaller::new(
+            self.server_config.clone(),
+            self.runtime.clone(),
+            self.query_state.clone(),
+        )
+        .call_as_string(self.tool_name.clone(), Some(arguments))
+        .await
+        .map(Some)
+    }
+}
+
+/// Values of a group.
+#[derive(Debug, Default)]
+struct Group {
+    values: Vec<Value>,
+    bytes: usize,
+}
+
+impl Group {
+    fn push(&mut self, value: Value, call: &McpAggCall) -> Result<()> {
+        self.bytes += value.to_string().len();
+        self.values.push(value);
+
+        if self.values.len() > call.max_items {
+            return exec_err!(
+                "{} collected more than {} values for a group. Filter the rows or raise max_items",
+                MCP_AGG_FUNCTION_NAME,
+                call.max_items
+            );
+        }
+        if self.bytes > call.max_bytes {
+            return exec_err!(
+                "{} collected more than {} bytes for a group. Filter the rows or raise max_bytes",
+                MCP_AGG_FUNCTION_NAME,
+                call.max_bytes
+            );
+        }
+        Ok(())
+    }
+
+    fn state(&self) -> String {
+        Value::Array(self.values.clone()).to_string()
+    }
+
+    fn merge(&mut self, state: &str, call: &McpAggCall) -> Result<()> {
+        let values: Vec<Value> = serde_json::from_str(state).map_err(|e| {
+            exec_datafusion_err!("invalid state of {}. {}", MCP_AGG_FUNCTION_NAME, e)
+        })?;
+        for value in values {
+            self.push(value, call)?;
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        size_of::<Self>() + self.bytes
+    }
+}
+
+#[derive(Debug)]
+struct McpAggAccumulator {
+    call: Arc<McpAggCall>,
+    group: Group,
+}
+
+impl Accumulator for McpAggAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        for value in array_to_values(&values[3])?.into_iter().flatten() {
+            self.group.push(value, &self.call)?;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let values = std::mem::take(&mut self.group.values);
+        let response = invoke_blocking(MCP_AGG_FUNCTION_NAME, self.call.call(values))?;
+        Ok(ScalarValue::Utf8(response))
+    }
+
+    fn size(&self) -> usize {
+        size_of::<Self>() + self.group.size()
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Utf8(Some(self.group.state()))])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        for state in states[0].as_string::<i32>().iter().flatten() {
+            self.group.merge(state, &self.call)?;
+        }
+        Ok(())
+    }
+}
+
+/// Calls the tool for the groups concurrently.
+#[derive(Debug)]
+struct McpAggGroupsAccumulator {
+    call: Arc<McpAggCall>,
+    groups: Vec<Group>,
+}
+
+impl GroupsAccumulator for McpAggGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.groups.resize_with(total_num_groups, Group::default);
+
+        let values = array_to_values(&values[3])?;
+        for (row, (value, group_index)) in values.into_iter().zip(group_indices).enumerate() {
+            let filtered = opt_filter.is_some_and(|f| !f.is_valid(row) || !f.value(row));
+            if let (Some(value), false) = (value, filtered) {
+                self.groups[*group_index].push(value, &self.call)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let groups = emit_to.take_needed(&mut self.groups);
+        let call = self.call.clone();
+        let responses: Vec<Option<String>> = invoke_blocking(
+            MCP_AGG_FUNCTION_NAME,
+            futures::stream::iter(groups)
+                .map(|group| call.call(group.values))
+                .buffered(MAX_CONCURRENT_CALLS)
+                .try_collect(),
+        )?;
+
+        Ok(Arc::new(StringArray::from(responses)))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let groups = emit_to.take_needed(&mut self.groups);
+        let states: StringArray = groups.iter().map(|group| Some(group.state())).collect();
+        Ok(vec![Arc::new(states)])
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.groups.resize_with(total_num_groups, Group::default);
+
+        let states = values[0].as_string::<i32>();
+        for (row, group_index) in group_indices.iter().enumerate() {
+            let filtered = opt_filter.is_some_and(|f| !f.is_valid(row) || !f.value(row));
+            if states.is_valid(row) && !filtered {
+                self.groups[*group_index].merge(states.value(row), &self.call)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        size_of::<Self>() + self.groups.iter().map(Group::size).sum::<usize>()
+    }
+}
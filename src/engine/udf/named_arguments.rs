@@ -0,0 +1,127 @@
+use crate::engine::udf::call_mcp_function::CALL_MCP_FUNCTION_NAME;
+use crate::engine::udf::exec_mcp_batch_function::EXEC_MCP_BATCH_FUNCTION_NAME;
+use crate::engine::udf::mcp_agg_function::MCP_AGG_FUNCTION_NAME;
+use datafusion::sql::parser::{CopyToSource, Statement};
+use datafusion::sql::sqlparser::ast::{
+    DictionaryField, Expr, Function, FunctionArg, FunctionArgExpr, FunctionArguments, ObjectName,
+    TableFactor, Value, VisitMut, VisitorMut,
+};
+use std::ops::ControlFlow;
+
+/// Functions with optional positional arguments, and the count of them.
+const POSITIONAL_ARGUMENT_COUNTS: &[(&str, usize)] = &[
+    (CALL_MCP_FUNCTION_NAME, 3),
+    (EXEC_MCP_BATCH_FUNCTION_NAME, 3),
+    (MCP_AGG_FUNCTION_NAME, 4),
+];
+
+const FUNCTIONS_WITH_OPTIONS: &[&str] = &[EXEC_MCP_BATCH_FUNCTION_NAME, MCP_AGG_FUNCTION_NAME];
+
+/// DataFusion drops named arguments of table functions, so this moves them into a trailing
+/// struct argument. e.g. `read_csv('a.csv', header => false)` to `read_csv('a.csv', {'header': false})`
+/// Same for the scalar and aggregate functions taking options, such as exec_mcp_batch.
+///
+/// Omitted optional arguments before the struct are filled with NULL.
+/// e.g. `call_mcp('s', 't', flatten => 1)` to `call_mcp('s', 't', NULL, {'flatten': 1})`
+pub fn rewrite_named_arguments(statement: &mut Statement) {
+    match statement {
+        Statement::Statement(statement) => {
+            let _ = statement.visit(&mut NamedArgumentsVisitor {});
+        }
+        Statement::Explain(explain) => rewrite_named_arguments(&mut explain.statement),
+        Statement::CopyTo(copy) => {
+            if let CopyToSource::Query(query) = &mut copy.source {
+                let _ = query.visit(&mut NamedArgumentsVisitor {});
+            }
+        }
+        Statement::CreateExternalTable(_) => {}
+    }
+}
+
+struct NamedArgumentsVisitor {}
+
+impl VisitorMut for NamedArgumentsVisitor {
+    type Break = ();
+
+    fn pre_visit_table_factor(&mut self, table_factor: &mut TableFactor) -> ControlFlow<()> {
+        if let TableFactor::Table {
+            name,
+            args: Some(args),
+            ..
+        } = table_factor
+        {
+            move_named_arguments(name, &mut args.args);
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<()> {
+        if let Expr::Function(Function {
+            name,
+            args: FunctionArguments::List(args),
+            ..
+        }) = expr
+            && is_function_with_options(name)
+        {
+            move_named_arguments(name, &mut args.args);
+        }
+
+        ControlFlow::Continue(())
+    }
+}
+
+/// Other functions are left as is unless they take options, since DataFusion handles the named
+/// arguments of some of them.
+fn is_function_with_options(name: &ObjectName) -> bool {
+    let function_name = function_name(name);
+    FUNCTIONS_WITH_OPTIONS.contains(&function_name.as_deref().unwrap_or_default())
+}
+
+fn function_name(name: &ObjectName) -> Option<String> {
+    name.0.last().map(|part| part.to_string().to_lowercase())
+}
+
+fn move_named_arguments(name: &ObjectName, args: &mut Vec<FunctionArg>) {
+    let mut fields = Vec::new();
+    args.retain(|arg| {
+        let (name, value) = match arg {
+            FunctionArg::Named {
+                name,
+                arg: FunctionArgExpr::Expr(value),
+                ..
+            } => (name.clone(), value),
+            FunctionArg::ExprNamed {
+                name: Expr::Identifier(name),
+                arg: FunctionArgExpr::Expr(value),
+                ..
+            } => (name.clone(), value),
+            _ => return true,
+        };
+        fields.push(DictionaryField {
+            key: name,
+            value: Box::new(value.clone()),
+        });
+        false
+    });
+
+    if fields.is_empty() {
+        return;
+    }
+
+    let function_name = function_name(name);
+    let positional_count = POSITIONAL_ARGUMENT_COUNTS
+        .iter()
+        .find(|(f, _)| function_name.as_deref() == Some(*f))
+        .map(|(_, count)| *count)
+        .unwrap_or_default();
+    while args.len() < positional_count {
+        args.push(FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Value(
+            Value::Null.into(),
+        ))));
+    }
+
+    args.push(FunctionArg::Unnamed(FunctionArgExpr::Expr(
+        Expr::Dictionary(fields),
+    )));
+}
@@ -0,0 +1,58 @@
+use crate::engine::access_policy::AccessPolicy;
+use datafusion::common::{Result, plan_datafusion_err, plan_err};
+use datafusion::execution::SessionState;
+use datafusion::logical_expr::Expr;
+use datafusion::scalar::ScalarValue;
+use datafusion_session::SessionStore;
+use std::sync::Arc;
+
+/// Session and access policy used by the read_* functions to resolve files while planning.
+#[derive(Debug, Clone)]
+pub struct ReadContext {
+    session_store: Arc<SessionStore>,
+    access_policy: Arc<AccessPolicy>,
+}
+
+impl ReadContext {
+    pub fn new(access_policy: Arc<AccessPolicy>) -> Self {
+        Self {
+            session_store: Arc::new(SessionStore::new()),
+            access_policy,
+        }
+    }
+
+    pub fn session_store(&self) -> &SessionStore {
+        &self.session_store
+    }
+
+    pub(super) fn state(&self) -> Result<SessionState> {
+        session_state(&self.session_store)
+    }
+
+    /// Returns the path in the first argument after checking the access policy.
+    pub(super) fn extract_path(&self, function_name: &str, args: &[Expr]) -> Result<String> {
+        let Some(Expr::Literal(ScalarValue::Utf8(Some(path)))) = args.first() else {
+            return plan_err!(
+                "first argument for {} must be an string (path)",
+                function_name
+            );
+        };
+        self.access_policy.check_url(path)?;
+
+        Ok(path.clone())
+    }
+}
+
+pub fn session_state(session_store: &SessionStore) -> Result<SessionState> {
+    session_store
+        .get_session()
+        .upgrade()
+        .and_then(|session| {
+            session
+                .read()
+                .as_any()
+                .downcast_ref::<SessionState>()
+                .cloned()
+        })
+        .ok_or_else(|| plan_datafusion_err!("get current SessionStore error"))
+}
@@ -0,0 +1,164 @@
+use crate::config::source_config::SourceConfig;
+use crate::engine::json_shape::JsonShape;
+use crate::engine::json_table::read_json_documents;
+use crate::engine::source_table::create_source_table;
+use crate::engine::udf::async_mcp_function::invoke_blocking;
+use crate::engine::udf::function_options::extract_options;
+use crate::engine::udf::read_context::ReadContext;
+use datafusion::catalog::{TableFunctionImpl, TableProvider};
+use datafusion::common::{Result, plan_datafusion_err, plan_err};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::logical_expr::Expr;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub const READ_CSV_FUNCTION_NAME: &str = "read_csv";
+pub const READ_JSON_FUNCTION_NAME: &str = "read_json";
+pub const READ_PARQUET_FUNCTION_NAME: &str = "read_parquet";
+
+#[derive(Debug, Clone, Copy)]
+pub enum ReadFormat {
+    Csv,
+    Json,
+    Parquet,
+}
+
+impl ReadFormat {
+    pub fn function_name(&self) -> &'static str {
+        match self {
+            ReadFormat::Csv => READ_CSV_FUNCTION_NAME,
+            ReadFormat::Json => READ_JSON_FUNCTION_NAME,
+            ReadFormat::Parquet => READ_PARQUET_FUNCTION_NAME,
+        }
+    }
+
+    fn file_type(&self) -> &'static str {
+        match self {
+            ReadFormat::Csv => "csv",
+            ReadFormat::Json => "json",
+            ReadFormat::Parquet => "parquet",
+        }
+    }
+
+    fn supported_options(&self) -> &'static [&'static str] {
+        match self {
+            ReadFormat::Csv => &[
+                "delimiter",
+                "header",
+                "quote",
+                "escape",
+                "comment",
+                "compression",
+                "schema",
+                "schema_infer_max_records",
+                "hive_partitioning",
+            ],
+            ReadFormat::Json => &[
+                "array_path",
+                "flatten",
+                "explode",
+                "compression",
+                "schema",
+                "schema_infer_max_records",
+                "hive_partitioning",
+            ],
+            ReadFormat::Parquet => &["schema", "hive_partitioning"],
+        }
+    }
+}
+
+/// Reads files with the options, same as `CREATE EXTERNAL TABLE ... OPTIONS (...)`.
+/// e.g. `SELECT * FROM read_csv('data/*.csv', delimiter => ';', header => false)`
+///
+/// read_json also reads JSON documents, which are an array or a pretty-printed object, selecting a
+/// nested array by `array_path`. `flatten` and `explode` reshape the rows.
+#[derive(Debug)]
+pub struct ReadFileFunction {
+    format: ReadFormat,
+    read_context: ReadContext,
+}
+
+impl ReadFileFunction {
+    pub fn new(format: ReadFormat, read_context: ReadContext) -> Self {
+        Self {
+            format,
+            read_context,
+        }
+    }
+}
+
+impl TableFunctionImpl for ReadFileFunction {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        let function_name = self.format.function_name();
+        if args.len() > 2 {
+            return plan_err!(
+                "{} takes a path and options. e.g. {}('data/*.{}', option => 'value')",
+                function_name,
+                function_name,
+                self.format.file_type()
+            );
+        }
+
+        let path = self.read_context.extract_path(function_name, args)?;
+        let mut options =
+            extract_options(function_name, args.get(1), self.format.supported_options())?;
+
+        let read_document = matches!(self.format, ReadFormat::Json);
+        let array_path = options.remove("array_path");
+        let shape = JsonShape::take_from(&mut options)?;
+        let hive_partitioning = match options.remove("hive_partitioning") {
+            Some(value) => value.parse().map_err(|_| {
+                plan_datafusion_err!("hive_partitioning must be true or false: {}", value)
+            })?,
+            None => false,
+        };
+
+        let source = SourceConfig {
+            name: function_name.to_string(),
+            path,
+            format: self.format.file_type().to_string(),
+            schema: options.remove("schema"),
+            options: Self::to_table_options(options),
+            partition_cols: vec![],
+            hive_partitioning,
+            description: None,
+        };
+
+        let state = self.read_context.state()?;
+        invoke_blocking(function_name, async move {
+            if read_document {
+                let table_url = ListingTableUrl::parse(&source.path)?;
+                if let Some(table) =
+                    read_json_documents(&state, &table_url, array_path.as_deref(), &shape).await?
+                {
+                    if source.schema.is_some() || !source.options.is_empty() {
+                        return plan_err!(
+                            "only array_path, flatten and explode are supported for JSON documents in {}",
+                            source.name
+                        );
+                    }
+                    return Ok(table);
+                }
+            }
+
+            create_source_table(&state, &source).await
+        })
+    }
+}
+
+impl ReadFileFunction {
+    /// Converts the option names to the ones for `OPTIONS`.
+    fn to_table_options(options: HashMap<String, String>) -> HashMap<String, String> {
+        options
+            .into_iter()
+            .map(|(key, value)| {
+                let key = match key.as_str() {
+                    "header" => "format.has_header".to_string(),
+                    "schema_infer_max_records" => "format.schema_infer_max_rec".to_string(),
+                    _ => format!("format.{}", key),
+                };
+                (key, value)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,79 @@
+use crate::engine::file_listing::{display_location, list_files, read_bytes};
+use crate::engine::udf::async_mcp_function::invoke_blocking;
+use crate::engine::udf::read_context::ReadContext;
+use datafusion::arrow::array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::catalog::{MemTable, TableFunctionImpl, TableProvider};
+use datafusion::common::{Result, plan_err};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::logical_expr::Expr;
+use std::sync::Arc;
+
+pub const READ_TEXT_FUNCTION_NAME: &str = "read_text";
+
+/// Reads each file as a row of `filename`, `content` and `size`.
+#[derive(Debug)]
+pub struct ReadTextFunction {
+    read_context: ReadContext,
+}
+
+impl ReadTextFunction {
+    pub fn new(read_context: ReadContext) -> Self {
+        Self { read_context }
+    }
+}
+
+impl TableFunctionImpl for ReadTextFunction {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        if args.len() != 1 {
+            return plan_err!(
+                "{} takes only a path. e.g. {}('notes/*.md')",
+                READ_TEXT_FUNCTION_NAME,
+                READ_TEXT_FUNCTION_NAME
+            );
+        }
+
+        let path = self
+            .read_context
+            .extract_path(READ_TEXT_FUNCTION_NAME, args)?;
+        let state = self.read_context.state()?;
+
+        let files = invoke_blocking(READ_TEXT_FUNCTION_NAME, async move {
+            let table_url = ListingTableUrl::parse(&path)?;
+            let (store, metas) = list_files(&state, &table_url).await?;
+
+            let mut files = Vec::with_capacity(metas.len());
+            for meta in metas {
+                let bytes = read_bytes(&store, &meta).await?;
+                let content = String::from_utf8_lossy(&bytes).to_string();
+                files.push((
+                    display_location(&table_url, &meta),
+                    content,
+                    meta.size as i64,
+                ));
+            }
+
+            Ok(files)
+        })?;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("filename", DataType::Utf8, false),
+            Field::new("content", DataType::Utf8, false),
+            Field::new("size", DataType::Int64, false),
+        ]));
+        let filenames: Vec<_> = files.iter().map(|f| f.0.clone()).collect();
+        let contents: Vec<_> = files.iter().map(|f| f.1.clone()).collect();
+        let sizes: Vec<_> = files.iter().map(|f| f.2).collect();
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(filenames)) as ArrayRef,
+                Arc::new(StringArray::from(contents)),
+                Arc::new(Int64Array::from(sizes)),
+            ],
+        )?;
+
+        Ok(Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))
+    }
+}
@@ -0,0 +1,48 @@
+use crate::engine::udf::async_mcp_function::invoke_blocking;
+use crate::engine::udf::function_options::extract_options;
+use crate::engine::udf::read_context::ReadContext;
+use crate::engine::xlsx_table::{XLSX_OPTIONS, XlsxOptions, read_xlsx_table};
+use datafusion::catalog::{TableFunctionImpl, TableProvider};
+use datafusion::common::{Result, plan_err};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::logical_expr::Expr;
+use std::sync::Arc;
+
+pub const READ_XLSX_FUNCTION_NAME: &str = "read_xlsx";
+
+/// Reads a sheet of an xlsx workbook.
+/// e.g. `SELECT * FROM read_xlsx('report.xlsx', sheet => 'Q1', range => 'A1:F200', header => true)`
+#[derive(Debug)]
+pub struct ReadXlsxFunction {
+    read_context: ReadContext,
+}
+
+impl ReadXlsxFunction {
+    pub fn new(read_context: ReadContext) -> Self {
+        Self { read_context }
+    }
+}
+
+impl TableFunctionImpl for ReadXlsxFunction {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        if args.len() > 2 {
+            return plan_err!(
+                "{} takes a path and options. e.g. {}('report.xlsx', sheet => 'Q1')",
+                READ_XLSX_FUNCTION_NAME,
+                READ_XLSX_FUNCTION_NAME
+            );
+        }
+
+        let path = self
+            .read_context
+            .extract_path(READ_XLSX_FUNCTION_NAME, args)?;
+        let options = extract_options(READ_XLSX_FUNCTION_NAME, args.get(1), XLSX_OPTIONS)?;
+        let options = XlsxOptions::from_map(&options)?;
+
+        let state = self.read_context.state()?;
+        invoke_blocking(READ_XLSX_FUNCTION_NAME, async move {
+            let table_url = ListingTableUrl::parse(&path)?;
+            read_xlsx_table(&state, &table_url, &options).await
+        })
+    }
+}
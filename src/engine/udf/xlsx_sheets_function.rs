@@ -0,0 +1,70 @@
+use crate::engine::udf::async_mcp_function::invoke_blocking;
+use crate::engine::udf::read_context::ReadContext;
+use crate::engine::xlsx_table::list_sheets;
+use datafusion::arrow::array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::catalog::{MemTable, TableFunctionImpl, TableProvider};
+use datafusion::common::{Result, plan_err};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::logical_expr::Expr;
+use std::sync::Arc;
+
+pub const XLSX_SHEETS_FUNCTION_NAME: &str = "xlsx_sheets";
+
+/// Lists the sheets of an xlsx workbook as rows of `sheet_name`, `sheet_index`, `rows` and
+/// `columns`, so that they can be found before reading with read_xlsx.
+#[derive(Debug)]
+pub struct XlsxSheetsFunction {
+    read_context: ReadContext,
+}
+
+impl XlsxSheetsFunction {
+    pub fn new(read_context: ReadContext) -> Self {
+        Self { read_context }
+    }
+}
+
+impl TableFunctionImpl for XlsxSheetsFunction {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        if args.len() != 1 {
+            return plan_err!(
+                "{} takes only a path. e.g. {}('report.xlsx')",
+                XLSX_SHEETS_FUNCTION_NAME,
+                XLSX_SHEETS_FUNCTION_NAME
+            );
+        }
+
+        let path = self
+            .read_context
+            .extract_path(XLSX_SHEETS_FUNCTION_NAME, args)?;
+        let state = self.read_context.state()?;
+
+        let sheets = invoke_blocking(XLSX_SHEETS_FUNCTION_NAME, async move {
+            let table_url = ListingTableUrl::parse(&path)?;
+            list_sheets(&state, &table_url).await
+        })?;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("sheet_name", DataType::Utf8, false),
+            Field::new("sheet_index", DataType::Int64, false),
+            Field::new("rows", DataType::Int64, false),
+            Field::new("columns", DataType::Int64, false),
+        ]));
+        let names: Vec<_> = sheets.iter().map(|s| s.name.clone()).collect();
+        let indexes: Vec<_> = (0..sheets.len() as i64).collect();
+        let rows: Vec<_> = sheets.iter().map(|s| s.rows as i64).collect();
+        let columns: Vec<_> = sheets.iter().map(|s| s.columns as i64).collect();
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(names)) as ArrayRef,
+                Arc::new(Int64Array::from(indexes)),
+                Arc::new(Int64Array::from(rows)),
+                Arc::new(Int64Array::from(columns)),
+            ],
+        )?;
+
+        Ok(Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))
+    }
+}
@@ -0,0 +1,374 @@
+use crate::engine::file_listing::{list_files, read_bytes};
+use calamine::{Data, Range, Reader, Xlsx};
+use datafusion::arrow::array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, RecordBatchOptions, StringArray,
+    TimestampMillisecondArray,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use datafusion::catalog::{MemTable, TableProvider};
+use datafusion::common::{Result, exec_datafusion_err, plan_err};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::execution::SessionState;
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::sync::Arc;
+
+pub const XLSX_OPTIONS: &[&str] = &["sheet", "range", "header"];
+
+/// Which cells of a workbook to read. The first sheet, its used range and a header row by default.
+#[derive(Debug, Clone)]
+pub struct XlsxOptions {
+    pub sheet: Option<String>,
+    pub range: Option<String>,
+    pub header: bool,
+}
+
+impl Default for XlsxOptions {
+    fn default() -> Self {
+        Self {
+            sheet: None,
+            range: None,
+            header: true,
+        }
+    }
+}
+
+impl XlsxOptions {
+    /// Options of `read_xlsx` or `CREATE EXTERNAL TABLE ... OPTIONS (...)`, where `format.` prefix
+    /// is accepted too.
+    pub fn from_map(options: &HashMap<String, String>) -> Result<Self> {
+        let mut xlsx_options = Self::default();
+        for (key, value) in options {
+            match key.strip_prefix("format.").unwrap_or(key) {
+                "sheet" => xlsx_options.sheet = Some(value.clone()),
+                "range" => xlsx_options.range = Some(value.clone()),
+                "header" => {
+                    xlsx_options.header = value.parse().map_err(|_| {
+                        exec_datafusion_err!("header for xlsx must be true or false: {}", value)
+                    })?
+                }
+                _ => {
+                    return plan_err!(
+                        "unknown option for xlsx. Option: {}, Supported: [{}]",
+                        key,
+                        XLSX_OPTIONS.join(", ")
+                    );
+                }
+            }
+        }
+
+        Ok(xlsx_options)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SheetInfo {
+    pub name: String,
+    pub rows: usize,
+    pub columns: usize,
+}
+
+/// Reads a sheet of the workbook as a table, inferring the type of each column.
+pub async fn read_xlsx_table(
+    state: &SessionState,
+    table_url: &ListingTableUrl,
+    options: &XlsxOptions,
+) -> Result<Arc<dyn TableProvider>> {
+    let mut workbook = open_workbook(state, table_url).await?;
+
+    let sheet = match &options.sheet {
+        Some(sheet) => sheet.clone(),
+        None => match workbook.sheet_names().first() {
+            Some(sheet) => sheet.clone(),
+            None => return plan_err!("no sheet in {}", table_url),
+        },
+    };
+    let mut range = workbook.worksheet_range(&sheet).map_err(|e| {
+        exec_datafusion_err!(
+            "failed to read sheet({}) of {}. {}. Sheets: [{}]",
+            sheet,
+            table_url,
+            e,
+            workbook.sheet_names().join(", ")
+        )
+    })?;
+    if let Some(a1_range) = &options.range {
+        let (start, end) = parse_range(a1_range)?;
+        range = clamp_range(&range, start, end);
+    }
+
+    let batch = range_to_batch(&range, options.header)?;
+    let mem = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+    Ok(Arc::new(mem))
+}
+
+/// Lists the sheets of the workbook in the workbook order.
+pub async fn list_sheets(
+    state: &SessionState,
+    table_url: &ListingTableUrl,
+) -> Result<Vec<SheetInfo>> {
+    let mut workbook = open_workbook(state, table_url).await?;
+
+    let mut sheets = vec![];
+    for name in workbook.sheet_names() {
+        let range = workbook
+            .worksheet_range(&name)
+            .map_err(|e| exec_datafusion_err!("failed to read sheet({}). {}", name, e))?;
+        let (rows, columns) = range.get_size();
+        sheets.push(SheetInfo {
+            name,
+            rows,
+            columns,
+        });
+    }
+
+    Ok(sheets)
+}
+
+async fn open_workbook(
+    state: &SessionState,
+    table_url: &ListingTableUrl,
+) -> Result<Xlsx<Cursor<Vec<u8>>>> {
+    let (store, metas) = list_files(state, table_url).await?;
+    let [meta] = metas.as_slice() else {
+        return plan_err!(
+            "xlsx must be a single file. {} files are found in {}",
+            metas.len(),
+            table_url
+        );
+    };
+
+    let bytes = read_bytes(&store, meta).await?;
+    Xlsx::new(Cursor::new(bytes))
+        .map_err(|e| exec_datafusion_err!("failed to open xlsx {}. {}", table_url, e))
+}
+
+/// Parses a range in A1 notation such as `A1:F200` into zero-based (row, column) positions.
+fn parse_range(range: &str) -> Result<((u32, u32), (u32, u32))> {
+    let parsed = range
+        .split_once(':')
+        .and_then(|(start, end)| Some((parse_cell(start)?, parse_cell(end)?)));
+
+    match parsed {
+        Some((start, end)) if start.0 <= end.0 && start.1 <= end.1 => Ok((start, end)),
+        _ => plan_err!(
+            "range must be in A1 notation. e.g. 'A1:F200'. input: {}",
+            range
+        ),
+    }
+}
+
+/// Cuts the range down to the used cells of the sheet, so that a range far larger than the sheet
+/// such as `A1:XFD1048576` allocates nothing beyond them.
+fn clamp_range(range: &Range<Data>, start: (u32, u32), end: (u32, u32)) -> Range<Data> {
+    let (Some(used_start), Some(used_end)) = (range.start(), range.end()) else {
+        return Range::empty();
+    };
+    let start = (start.0.max(used_start.0), start.1.max(used_start.1));
+    let end = (end.0.min(used_end.0), end.1.min(used_end.1));
+    if start.0 > end.0 || start.1 > end.1 {
+        return Range::empty();
+    }
+
+    range.range(start, end)
+}
+
+fn parse_cell(cell: &str) -> Option<(u32, u32)> {
+    let cell = cell.trim().replace('$', "").to_uppercase();
+    let digits_at = cell.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = cell.split_at(digits_at);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+
+    let column = letters.chars().try_fold(0u32, |acc, c| {
+        acc.checked_mul(26)?.checked_add(c as u32 - 'A' as u32 + 1)
+    })?;
+    let row = digits.parse::<u32>().ok()?;
+    if row == 0 {
+        return None;
+    }
+
+    Some((row - 1, column - 1))
+}
+
+fn range_to_batch(range: &Range<Data>, header: bool) -> Result<RecordBatch> {
+    let rows: Vec<&[Data]> = range.rows().collect();
+    let width = range.width();
+
+    let (names, body) = match rows.split_first() {
+        Some((first, body)) if header => (column_names(first, width), body),
+        _ => (
+            (1..=width).map(|i| format!("column_{}", i)).collect(),
+            rows.as_slice(),
+        ),
+    };
+
+    let mut fields = Vec::with_capacity(width);
+    let mut arrays = Vec::with_capacity(width);
+    for (i, name) in names.into_iter().enumerate() {
+        let cells: Vec<&Data> = body
+            .iter()
+            .map(|row| row.get(i).unwrap_or(&Data::Empty))
+            .collect();
+        let array = cells_to_array(&cells);
+        fields.push(Field::new(name, array.data_type().clone(), true));
+        arrays.push(array);
+    }
+
+    let schema = Arc::new(Schema::new(fields));
+    Ok(RecordBatch::try_new_with_options(
+        schema,
+        arrays,
+        &RecordBatchOptions::new().with_row_count(Some(body.len())),
+    )?)
+}
+
+/// Uses the header cells as the column names, naming empty and duplicated ones by the position.
+fn column_names(header: &[Data], width: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    (0..width)
+        .map(|i| {
+            let name = header
+                .get(i)
+                .map(|cell| cell.to_string().trim().to_string())
+                .unwrap_or_default();
+            if name.is_empty() || !seen.insert(name.clone()) {
+                format!("column_{}", i + 1)
+            } else {
+                name
+            }
+        })
+        .collect()
+}
+
+fn infer_type(cells: &[&Data]) -> DataType {
+    let mut data_type: Option<DataType> = None;
+    for cell in cells {
+        let cell_type = match cell {
+            Data::Empty | Data::Error(_) => continue,
+            Data::Int(_) => DataType::Int64,
+            Data::Float(f) if f.fract() == 0.0 && f.abs() < 9.0e15 => DataType::Int64,
+            Data::Float(_) => DataType::Float64,
+            Data::Bool(_) => DataType::Boolean,
+            Data::DateTime(dt) if !dt.is_duration() => {
+                DataType::Timestamp(TimeUnit::Millisecond, None)
+            }
+            _ => DataType::Utf8,
+        };
+
+        data_type = Some(match data_type {
+            None => cell_type,
+            Some(current) if current == cell_type => current,
+            Some(DataType::Int64) if cell_type == DataType::Float64 => DataType::Float64,
+            Some(DataType::Float64) if cell_type == DataType::Int64 => DataType::Float64,
+            Some(_) => DataType::Utf8,
+        });
+    }
+
+    data_type.unwrap_or(DataType::Utf8)
+}
+
+fn cells_to_array(cells: &[&Data]) -> ArrayRef {
+    match infer_type(cells) {
+        DataType::Int64 => Arc::new(
+            cells
+                .iter()
+                .map(|cell| match cell {
+                    Data::Int(i) => Some(*i),
+                    Data::Float(f) => Some(*f as i64),
+                    _ => None,
+                })
+                .collect::<Int64Array>(),
+        ),
+        DataType::Float64 => Arc::new(
+            cells
+                .iter()
+                .map(|cell| match cell {
+                    Data::Int(i) => Some(*i as f64),
+                    Data::Float(f) => Some(*f),
+                    _ => None,
+                })
+                .collect::<Float64Array>(),
+        ),
+        DataType::Boolean => Arc::new(
+            cells
+                .iter()
+                .map(|cell| match cell {
+                    Data::Bool(b) => Some(*b),
+                    _ => None,
+                })
+                .collect::<BooleanArray>(),
+        ),
+        DataType::Timestamp(_, _) => Arc::new(
+            cells
+                .iter()
+                .map(|cell| match cell {
+                    Data::DateTime(dt) => {
+                        dt.as_datetime().map(|dt| dt.and_utc().timestamp_millis())
+                    }
+                    _ => None,
+                })
+                .collect::<TimestampMillisecondArray>(),
+        ),
+        _ => Arc::new(
+            cells
+                .iter()
+                .map(|cell| match cell {
+                    Data::Empty | Data::Error(_) => None,
+                    cell => Some(cell.to_string()),
+                })
+                .collect::<StringArray>(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cell_reads_a1_notation() {
+        assert_eq!(parse_cell("A1"), Some((0, 0)));
+        assert_eq!(parse_cell("$c$12"), Some((11, 2)));
+        assert_eq!(parse_cell("AA3"), Some((2, 26)));
+        assert_eq!(parse_cell("XFD1048576"), Some((1048575, 16383)));
+        assert_eq!(parse_cell("A0"), None);
+        assert_eq!(parse_cell("1A"), None);
+        assert_eq!(parse_cell("A"), None);
+        assert_eq!(parse_cell("A99999999999"), None);
+        assert_eq!(parse_cell("ZZZZZZZZ1"), None);
+    }
+
+    #[test]
+    fn parse_range_rejects_inverted_and_malformed_ranges() {
+        assert_eq!(parse_range("A1:F200").unwrap(), ((0, 0), (199, 5)));
+        assert_eq!(
+            parse_range("A1:XFD1048576").unwrap(),
+            ((0, 0), (1048575, 16383))
+        );
+        assert!(parse_range("F200:A1").is_err());
+        assert!(parse_range("B1:A2").is_err());
+        assert!(parse_range("A2:B1").is_err());
+        assert!(parse_range("A1").is_err());
+        assert!(parse_range("A1:").is_err());
+    }
+
+    #[test]
+    fn clamp_range_keeps_to_the_used_cells() {
+        let mut range = Range::new((1, 1), (2, 2));
+        range.set_value((1, 1), Data::Int(1));
+        range.set_value((2, 2), Data::Int(4));
+
+        let huge = clamp_range(&range, (0, 0), (1048575, 16383));
+        assert_eq!(huge.start(), Some((1, 1)));
+        assert_eq!(huge.end(), Some((2, 2)));
+
+        let part = clamp_range(&range, (2, 0), (2000000, 1));
+        assert_eq!(part.start(), Some((2, 1)));
+        assert_eq!(part.end(), Some((2, 1)));
+
+        assert!(clamp_range(&range, (5, 5), (9, 9)).is_empty());
+        assert!(clamp_range(&Range::empty(), (0, 0), (9, 9)).is_empty());
+    }
+}
@@ -0,0 +1,32 @@
+use crate::engine::xlsx_table::{XlsxOptions, read_xlsx_table};
+use async_trait::async_trait;
+use datafusion::catalog::{Session, TableProvider, TableProviderFactory};
+use datafusion::common::{Result, plan_datafusion_err};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::execution::SessionState;
+use datafusion::logical_expr::CreateExternalTable;
+use std::sync::Arc;
+
+pub const XLSX_FILE_TYPE: &str = "XLSX";
+
+/// Creates tables for `CREATE EXTERNAL TABLE ... STORED AS XLSX OPTIONS ('sheet' 'Q1')`.
+#[derive(Debug, Default)]
+pub(super) struct XlsxTableFactory {}
+
+#[async_trait]
+impl TableProviderFactory for XlsxTableFactory {
+    async fn create(
+        &self,
+        state: &dyn Session,
+        cmd: &CreateExternalTable,
+    ) -> Result<Arc<dyn TableProvider>> {
+        let state = state
+            .as_any()
+            .downcast_ref::<SessionState>()
+            .ok_or_else(|| plan_datafusion_err!("xlsx tables need a SessionState"))?;
+        let options = XlsxOptions::from_map(&cmd.options)?;
+        let table_url = ListingTableUrl::parse(&cmd.location)?;
+
+        read_xlsx_table(state, &table_url, &options).await
+    }
+}
@@ -1,7 +1,7 @@
 use crate::config::mcp_server_config::McpServerConfig;
 use datafusion::common::{Result, exec_err};
 use rmcp::model::{CallToolRequestParam, CallToolResult, JsonObject, PaginatedRequestParam};
-use rmcp::model::{PaginatedRequestParamInner, Tool};
+use rmcp::model::{ServerInfo, Tool};
 use rmcp::service::RunningService;
 use rmcp::transport::TokioChildProcess;
 use rmcp::{RoleClient, ServiceExt};
@@ -22,9 +22,7 @@ impl McpClient {
         let service = self.connect_service().await?;
 
         let mut found_tools = Vec::<Tool>::new();
-        let mut list_params: PaginatedRequestParam = Some(PaginatedRequestParamInner {
-            cursor: Some("".to_string()),
-        });
+        let mut list_params = Some(PaginatedRequestParam { cursor: None });
         loop {
             let response = match service.list_tools(list_params).await {
                 Ok(response) => response,
@@ -45,8 +43,8 @@ impl McpClient {
             let Some(cursor) = response.next_cursor else {
                 break;
             };
-            list_params = Some(PaginatedRequestParamInner {
-                cursor: cursor.into(),
+            list_params = Some(PaginatedRequestParam {
+                cursor: Some(cursor),
             });
         }
 
@@ -54,6 +52,14 @@ impl McpClient {
         Ok(found_tools)
     }
 
+    pub async fn initialize(&self) -> Result<ServerInfo> {
+        let service = self.connect_service().await?;
+        let server_info = service.peer_info().cloned().unwrap_or_default();
+
+        self.cancel_service(service).await?;
+        Ok(server_info)
+    }
+
     pub async fn call_tool(
         &self,
         name: &str,
@@ -88,8 +94,11 @@ impl McpClient {
             cmd.env(k, v);
         }
 
-        let tokio_process = TokioChildProcess::new(&mut Command::from(cmd))?;
-        Ok(().serve(tokio_process).await?)
+        let tokio_process = TokioChildProcess::new(Command::from(cmd))?;
+        match ().serve(tokio_process).await {
+            Ok(service) => Ok(service),
+            Err(e) => exec_err!("failed to connect mcp-server({}). {}", self.config.name, e),
+        }
     }
 
     async fn cancel_service(&self, service: RunningService<RoleClient, ()>) -> Result<()> {
@@ -1 +1,3 @@
 pub mod mcp_tool;
+pub mod query_result;
+pub mod query_source;
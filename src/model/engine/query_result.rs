@@ -0,0 +1,8 @@
+use datafusion::arrow::array::RecordBatch;
+
+#[derive(Debug)]
+pub struct QueryResult {
+    pub batches: Vec<RecordBatch>,
+    /// Set when the result is truncated by the limits.
+    pub notice: Option<String>,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuerySource {
+    Repl,
+    McpClient,
+    Agent,
+}
+
+impl Display for QuerySource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            QuerySource::Repl => write!(f, "repl"),
+            QuerySource::McpClient => write!(f, "mcp_client"),
+            QuerySource::Agent => write!(f, "agent"),
+        }
+    }
+}
@@ -18,7 +18,7 @@ const LIST_MCP_TOOL_SQL: &str = "SELECT server_name, tool_name, description FROM
 
 impl McpRepo {
     pub async fn list_mcp_tools(&self) -> Result<Vec<McpTool>> {
-        let res = self.ctx.run_sql(LIST_MCP_TOOL_SQL).await?.collect().await?;
+        let res = self.ctx.run_sql(LIST_MCP_TOOL_SQL).await?;
 
         let mut ret = Vec::<McpTool>::new();
         for batch in res {
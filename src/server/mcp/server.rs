@@ -1,28 +1,26 @@
 use crate::engine::context::Context;
+use crate::model::engine::query_source::QuerySource;
 use crate::util::arrow::json::convert_to_json;
+use rmcp::handler::server::router::tool::ToolRouter;
+use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::{
     CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
 };
-use rmcp::tool;
-use rmcp::{Error as McpError, ServerHandler};
+use rmcp::schemars::JsonSchema;
+use rmcp::{ErrorData as McpError, ServerHandler, tool, tool_handler, tool_router};
+use serde::Deserialize;
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct McpServer {
     context: Arc<Context>,
+    tool_router: ToolRouter<Self>,
 }
 
-#[tool(tool_box)]
-impl McpServer {
-    pub fn new(context: Arc<Context>) -> Self {
-        Self { context }
-    }
-
-    #[tool(description = "Run SQL query")]
-    async fn run_query(
-        &self,
-        #[tool(param)]
-        #[schemars(description = "Execute SQL
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(crate = "rmcp::schemars")]
+struct RunQueryParams {
+    #[schemars(description = "Execute SQL
 <long-description>
 This MCP server, called DataHarpoon, functions as a database and supports DataFusion SQL, which is largely compatible with standard SQL syntax.
 By executing SQL queries, you can retrieve and aggregate data as needed.
@@ -54,13 +52,67 @@ Schema: ```sql
     server_name VARCHAR,     -- Value to be passed as the `server_name` argument of `call_mcp` and `exec_mcp`
     tool_name VARCHAR,       -- Value to be passed as the `tool_name` argument of `call_mcp` and `exec_mcp`
     description VARCHAR,     -- Describes the output or purpose of the tool
-    input_schema VARCHAR     -- JSON Schema defining the structure of the `args` parameter for `call_mcp` and `exec_mcp`
+    input_schema VARCHAR,    -- JSON Schema defining the structure of the `args` parameter for `call_mcp` and `exec_mcp`
+    read_only_hint BOOLEAN,  -- true when the tool does not modify its environment. Tools not being read-only may be denied.
+    destructive_hint BOOLEAN,
+    idempotent_hint BOOLEAN,
+    open_world_hint BOOLEAN
   );
 ```
 Example Query: ```sql
   SELECT * FROM information_schema.mcp_tools WHERE server_name = 'awesome_server';
 ```
 
+## information_schema.sources
+
+Table Name: information_schema.sources
+Description: Contains the datasets declared by the user. Each `name` can be used as a table name.
+Schema: ```sql
+  CREATE TABLE information_schema.sources (
+    name VARCHAR,         -- Table name of the dataset
+    format VARCHAR,       -- File format such as csv, json and parquet
+    path VARCHAR,         -- File, directory or glob the table reads
+    description VARCHAR
+  );
+```
+Example Query: ```sql
+  SELECT * FROM information_schema.sources;
+```
+
+## SQLite databases
+
+SQLite databases attached by the user (or by `ATTACH 'app.db' AS app`) are catalogs. Their tables are in the `main` schema, e.g. `app.main.users`.
+Example Query: ```sql
+  SELECT name, type FROM app.main.sqlite_master;
+```
+
+## information_schema.materialized_views
+
+Table Name: information_schema.materialized_views
+Description: Materialized views, whose results are snapshotted to Parquet. Each `name` can be used as a table name without calling MCP tools. A snapshot older than `ttl_secs` is refreshed when read. `REFRESH MATERIALIZED VIEW issues` refreshes it on demand.
+Schema: ```sql
+  CREATE TABLE information_schema.materialized_views (
+    name VARCHAR,
+    sql VARCHAR,              -- Query of the view
+    ttl_secs BIGINT UNSIGNED, -- Refreshed only on demand when NULL
+    refreshed_at TIMESTAMP,   -- When the snapshot was taken
+    expires_at TIMESTAMP,
+    row_count BIGINT,
+    file VARCHAR              -- Parquet file of the snapshot
+  );
+```
+Example Query: ```sql
+  SELECT name, refreshed_at FROM information_schema.materialized_views;
+  CREATE MATERIALIZED VIEW issues WITH (ttl_secs = 3600) AS SELECT * FROM call_mcp('awesome_server', 'list_issues');
+```
+
+## MCP sinks
+
+`INSERT INTO` a table created by `CREATE EXTERNAL TABLE messages STORED AS MCP LOCATION 'awesome_server/send_message'` calls the tool once per row, passing the non-null columns as the arguments. The columns are taken from the input schema of the tool when omitted. It returns the numbers of `succeeded` and `failed` calls and the `first_error`. Tools not annotated as read-only follow the tool policy, and sinks can't be selected from.
+Example Query: ```sql
+  INSERT INTO messages SELECT 'general' AS channel, title AS text FROM call_mcp('awesome_server', 'list_issues');
+```
+
 # Available Functions
 
 ## call_mcp
@@ -70,6 +122,11 @@ Arguments:
   1. server_name – Name of the MCP server to execute against
   2. tool_name – Name of the MCP tool to be executed
   3. args – Arguments for the MCP tool, formatted according to the input_schema in information_schema.mcp_tools. (Note: Arguments should be specified as a map (e.g., {'key': 'value'}), representing a JSON object defined by the input_schema.)
+  4. options – Named arguments to reshape the result.
+     * flatten: Expands nested objects into `user_login`-style columns up to the depth. `true` expands all levels.
+     * explode: A list column to turn into one row per element. Rows with an empty list are dropped.
+     * schema: The columns of the result. e.g. `schema => 'id BIGINT, title VARCHAR'`. Objects and arrays in VARCHAR columns are kept as JSON text.
+  The tool is called when the query is executed, so `EXPLAIN` shows the pending call. The columns come from `schema`, the output schema of the tool, or the last result of the same call. When none of them is known, a tool annotated as read-only is called once while planning and its result is used by the query. Other tools need `schema`. `EXPLAIN` doesn't call the tool and shows `schema=unknown` with no columns instead.
 Examples:
   * When arguments are provided:
     ```sql
@@ -79,9 +136,13 @@ Examples:
     ```sql
     SELECT * FROM call_mcp('awesome_server', 'awesome_tool');
     ```
+  * When the result is nested:
+    ```sql
+    SELECT user_login, labels_name FROM call_mcp('awesome_server', 'list_issues', {'repo': 'awesome'}, flatten => 2, explode => 'labels');
+    ```
 ## exec_mcp
 Function Name: exec_mcp
-Description: Executes an MCP tool with the given arguments and returns the response text. exec_mcp accepts the same arguments as call_mcp, but specifically for the value.
+Description: Executes an MCP tool with the given arguments and returns the response text. exec_mcp accepts the same arguments as call_mcp, but specifically for the value. The calls of a query run when it's executed, up to 8 at a time, and cancelling the query cancels the calls in flight. A statement fails when it reaches the limit of MCP calls, which `SET dataharpoon.max_mcp_calls = 100` changes for the session. Filter or limit the rows before calling tools over a large table.
 Arguments:
   1. server_name – Name of the MCP server to execute against
   2. tool_name – Name of the MCP tool to be executed
@@ -95,22 +156,133 @@ Examples:
     ```sql
     SELECT exec_mcp('awesome_server', 'awesome_tool') AS awesome_value;
     ```
+## exec_mcp_batch
+Function Name: exec_mcp_batch
+Description: Same as exec_mcp, but sends the arguments of up to `batch_size` rows to one call as an array, and returns the element of the returned array for each row. Use it for tools taking a list of inputs, such as LLM tools. The tool must return an array with one result per item, in the same order.
+Arguments:
+  1. server_name – Name of the MCP server to execute against
+  2. tool_name – Name of the MCP tool to be executed
+  3. item – The value sent for the row, such as a column or a map (e.g., {'text': body}). Rows of NULL are not sent and get NULL.
+  4. options – Named arguments.
+     * batch_arg: Name of the tool argument taking the array. Required.
+     * batch_size: Maximum rows sent by a call. Defaults to 50.
+Examples:
+    ```sql
+    SELECT title, exec_mcp_batch('awesome_server', 'classify', {'text': body}, batch_arg => 'items', batch_size => 20) AS label FROM issues;
+    ```
+## mcp_agg
+Function Name: mcp_agg
+Description: Aggregate function calling the MCP tool once per group with the values of the group. `{values}` in the strings of the template is replaced with the values, one per line. A string of just `'{values}'` passes them as a JSON array. Groups without values get NULL. A group over `max_items` values or `max_bytes` of JSON fails the query before any call.
+Arguments:
+  1. server_name – Name of the MCP server to execute against
+  2. tool_name – Name of the MCP tool to be executed
+  3. arg_template – Map of the tool arguments with `{values}` in a value (e.g., {'text': 'Summarize:\\n{values}'})
+  4. value – The column collected for the group. NULL values are skipped.
+  5. options – Named arguments.
+     * max_items: Maximum values of a group. Defaults to 1000 or `mcp_agg_max_items` of `[limits]`.
+     * max_bytes: Maximum bytes of the values as JSON. Defaults to 1 MiB or `mcp_agg_max_bytes` of `[limits]`.
+Examples:
+    ```sql
+    SELECT label, mcp_agg('awesome_server', 'summarize', {'text': 'Summarize these issues:\\n{values}'}, title) AS summary FROM issues GROUP BY label;
+    SELECT mcp_agg('awesome_server', 'classify', {'items': '{values}'}, body, max_items => 100) FROM issues;
+    ```
+## read_csv / read_json / read_parquet
+Function Name: read_csv, read_json, read_parquet
+Description: Reads files with options. Use these instead of `'file.csv'` when the file needs non-default options.
+Arguments:
+  1. path – File, directory or glob (e.g. 'data/*.csv')
+  2. options – Named arguments.
+     * read_csv: delimiter, header, quote, escape, comment, compression, schema, schema_infer_max_records, hive_partitioning
+     * read_json: array_path, flatten, explode, compression, schema, schema_infer_max_records, hive_partitioning
+       JSON arrays and pretty-printed objects are read as rows. `array_path` selects a nested array such as 'data.items'.
+       `flatten` and `explode` reshape the rows same as call_mcp.
+     * read_parquet: schema, hive_partitioning
+     `schema` is column definitions such as 'id BIGINT, name VARCHAR'.
+     `hive_partitioning => true` adds the columns of `key=value` directories such as `date=2025-06-01/`. Filters on them skip the other directories.
+Examples:
+    ```sql
+    SELECT * FROM read_csv('data/*.csv', delimiter => ';', header => false);
+    SELECT * FROM read_parquet('exports/', hive_partitioning => true) WHERE date >= '2025-06-01';
+    ```
+## read_text
+Function Name: read_text
+Description: Reads each file as a row of `filename`, `content` and `size`.
+Arguments:
+  1. path – File, directory or glob
+Examples:
+    ```sql
+    SELECT filename, content FROM read_text('notes/*.md');
+    ```
+## read_xlsx / xlsx_sheets
+Function Name: read_xlsx, xlsx_sheets
+Description: read_xlsx reads a sheet of an Excel workbook (.xlsx), inferring the type of each column. `'report.xlsx'` as a table name reads the first sheet. xlsx_sheets lists the sheets as rows of `sheet_name`, `sheet_index`, `rows` and `columns`.
+Arguments:
+  1. path – Workbook file
+  2. options – Named arguments of read_xlsx.
+     * sheet: Sheet name. The first sheet by default.
+     * range: Cells in A1 notation such as 'A1:F200'. The used cells by default.
+     * header: Whether the first row is the column names. true by default.
+Examples:
+    ```sql
+    SELECT sheet_name FROM xlsx_sheets('report.xlsx');
+    SELECT * FROM read_xlsx('report.xlsx', sheet => 'Q1', range => 'A1:F200', header => true);
+    ```
+## json_get_str / json_get_int / json_get_float / json_get_bool
+Function Name: json_get_str, json_get_int, json_get_float, json_get_bool
+Description: Gets a value from a JSON string by keys and array indexes, such as the result of exec_mcp. Returns NULL when the value is missing or has another type.
+Arguments:
+  1. json – JSON string
+  2. keys – Object keys (strings) and array indexes (integers), in order
+Examples:
+    ```sql
+    SELECT json_get_str(exec_mcp('awesome_server', 'get_user', {'id': 1}), 'profile', 'name');
+    SELECT json_get_int(response, 'items', 0, 'count') FROM awesome_table;
+    ```
+## json_extract / json_array_length / json_keys
+Function Name: json_extract, json_array_length, json_keys
+Description: json_extract returns the JSON text at the path. json_array_length returns the length of the array and json_keys the keys of the object at the path. The path is optional.
+Arguments:
+  1. json – JSON string
+  2. path – JSON path such as '$.items[0].name'
+Examples:
+    ```sql
+    SELECT json_extract(response, '$.items[0]'), json_array_length(response, '$.items') FROM awesome_table;
+    SELECT json_keys(exec_mcp('awesome_server', 'awesome_tool'));
+    ```
+## json_each
+Function Name: json_each
+Description: Returns the elements of a JSON array or the members of a JSON object as rows of `key` (object key or array index), `value` (JSON text) and `type` (object, array, string, number, boolean or null).
+Arguments:
+  1. json – JSON string. An expression such as exec_mcp is evaluated once when the query is executed, so EXPLAIN doesn't call the tool.
+  2. path – Optional JSON path of the array or object
+Examples:
+    ```sql
+    SELECT json_get_str(value, 'title') FROM json_each(exec_mcp('awesome_server', 'list_issues'), '$.issues');
+    ```
 
 </long-description>")]
-        query: String,
-    ) -> Result<CallToolResult, McpError> {
-        let res = self.context.run_sql(query.as_str()).await;
-        let df = match res {
-            Ok(df) => df,
-            Err(e) => {
-                return Ok(CallToolResult::error(vec![Content::text(format!(
-                    "invalid query. {:?}",
-                    e
-                ))]));
-            }
-        };
+    query: String,
+}
 
-        let arrow = match df.collect().await {
+#[tool_router]
+impl McpServer {
+    pub fn new(context: Arc<Context>) -> Self {
+        Self {
+            context,
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Run SQL query")]
+    async fn run_query(
+        &self,
+        Parameters(RunQueryParams { query }): Parameters<RunQueryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let res = self
+            .context
+            .execute_sql(query.as_str(), QuerySource::McpClient)
+            .await;
+        let arrow = match res {
             Err(e) => {
                 return Ok(CallToolResult::error(vec![Content::text(format!(
                     "invalid query. {:?}",
@@ -120,7 +292,7 @@ Examples:
             Ok(v) => v,
         };
 
-        let result = match convert_to_json(&arrow).await {
+        let result = match convert_to_json(&arrow.batches).await {
             Ok(result) => result,
             Err(e) => {
                 return Ok(CallToolResult::error(vec![Content::text(format!(
@@ -131,11 +303,15 @@ Examples:
         };
 
         let result_json = String::from_utf8_lossy(result.as_slice());
-        Ok(CallToolResult::success(vec![Content::text(result_json)]))
+        let mut contents = vec![Content::text(result_json)];
+        if let Some(notice) = arrow.notice {
+            contents.push(Content::text(notice));
+        }
+        Ok(CallToolResult::success(contents))
     }
 }
 
-#[tool(tool_box)]
+#[tool_handler]
 impl ServerHandler for McpServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -144,6 +320,7 @@ impl ServerHandler for McpServer {
             server_info: Implementation {
                 name: "DataHarpoon".into(),
                 version: "v0.0.1".into(),
+                ..Default::default()
             },
             instructions: None,
         }
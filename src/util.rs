@@ -1 +1,3 @@
 pub mod arrow;
+pub mod path;
+pub mod redact;
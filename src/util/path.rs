@@ -0,0 +1,10 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Expands a leading `~/` of a configured path to the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var("HOME")) {
+        (Some(rest), Ok(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
@@ -0,0 +1,202 @@
+use regex::Regex;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+pub const REDACTED_TEXT: &str = "***";
+
+/// Keys of secrets, matched against the last segments of a name split at `_`, `-`, `.` and
+/// camelCase. e.g. `GITHUB_TOKEN` and `apiKey` match, but `max_tokens` and `token_count` don't.
+const SECRET_KEYS: &[&[&str]] = &[
+    &["token"],
+    &["secret"],
+    &["password"],
+    &["passwd"],
+    &["api", "key"],
+    &["apikey"],
+    &["access", "key"],
+    &["private", "key"],
+    &["credential"],
+    &["credentials"],
+    &["authorization"],
+];
+
+// e.g. `--token=abc`, `API_KEY=abc`
+static ASSIGNMENT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(-{0,2}([\w.-]+)[=:])(.+)$").unwrap());
+
+// e.g. `--token`, `--api-key`. The next value is treated as a secret.
+static FLAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^-{1,2}([\w.-]+)$").unwrap());
+
+// Well-known token formats. e.g. GitHub (`ghp_...`), OpenAI/Anthropic (`sk-...`), Slack (`xoxb-...`)
+static SECRET_VALUE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:gh[pousr]_[A-Za-z0-9]{16,}|github_pat_\w{16,}|sk-[\w-]{16,}|xox[abprs]-[\w-]{10,}|AKIA[0-9A-Z]{16}|Bearer\s+\S+)$")
+        .unwrap()
+});
+
+/// Redacts values that look like secrets from command-line arguments.
+pub fn redact_args(args: &[String]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut redact_next = false;
+    for arg in args {
+        if redact_next {
+            redacted.push(REDACTED_TEXT.to_string());
+            redact_next = false;
+            continue;
+        }
+
+        if FLAG_REGEX
+            .captures(arg)
+            .is_some_and(|captures| is_secret_key(&captures[1]))
+        {
+            redact_next = true;
+            redacted.push(arg.clone());
+            continue;
+        }
+
+        redacted.push(redact_value(arg));
+    }
+
+    redacted
+}
+
+/// Redacts a single value when it looks like a secret.
+pub fn redact_value(value: &str) -> String {
+    if let Some(captures) = ASSIGNMENT_REGEX.captures(value)
+        && is_secret_key(&captures[2])
+    {
+        return format!("{}{}", &captures[1], REDACTED_TEXT);
+    }
+
+    if SECRET_VALUE_REGEX.is_match(value) {
+        return REDACTED_TEXT.to_string();
+    }
+
+    value.to_string()
+}
+
+/// Redacts values of a JSON value whose key or content looks like a secret.
+/// All the values are redacted when `redact_all` is true.
+pub fn redact_json(value: &Value, redact_all: bool) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let redacted = if is_secret_key(k) {
+                        Value::String(REDACTED_TEXT.to_string())
+                    } else {
+                        redact_json(v, redact_all)
+                    };
+                    (k.clone(), redacted)
+                })
+                .collect(),
+        ),
+        Value::Array(values) => {
+            Value::Array(values.iter().map(|v| redact_json(v, redact_all)).collect())
+        }
+        Value::Null => Value::Null,
+        _ if redact_all => Value::String(REDACTED_TEXT.to_string()),
+        Value::String(text) => Value::String(redact_value(text)),
+        _ => value.clone(),
+    }
+}
+
+fn is_secret_key(name: &str) -> bool {
+    let segments = key_segments(name);
+    SECRET_KEYS.iter().any(|key| {
+        segments.len() >= key.len()
+            && segments[segments.len() - key.len()..]
+                .iter()
+                .zip(key.iter())
+                .all(|(segment, key)| segment == key)
+    })
+}
+
+/// Splits a name such as `x-api-key`, `API_KEY` or `apiKey` into lowercase segments.
+fn key_segments(name: &str) -> Vec<String> {
+    let mut segments = vec![];
+    let mut segment = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !segment.is_empty() {
+                segments.push(std::mem::take(&mut segment));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower {
+            segments.push(std::mem::take(&mut segment));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        segment.extend(c.to_lowercase());
+    }
+    if !segment.is_empty() {
+        segments.push(segment);
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn is_secret_key_matches_whole_segments() {
+        for key in [
+            "token",
+            "access_token",
+            "GITHUB_TOKEN",
+            "apiKey",
+            "x-api-key",
+            "client.secret",
+            "password",
+            "Authorization",
+        ] {
+            assert!(is_secret_key(key), "{}", key);
+        }
+        for key in [
+            "max_tokens",
+            "token_count",
+            "credential_type",
+            "secretary",
+            "keyword",
+            "passwords_checked",
+        ] {
+            assert!(!is_secret_key(key), "{}", key);
+        }
+    }
+
+    #[test]
+    fn redact_json_keeps_values_of_look_alike_keys() {
+        let value = json!({"max_tokens": 100, "api_key": "abc", "nested": [{"auth_token": "x"}]});
+        assert_eq!(
+            redact_json(&value, false),
+            json!({"max_tokens": 100, "api_key": "***", "nested": [{"auth_token": "***"}]})
+        );
+    }
+
+    #[test]
+    fn redact_args_redacts_secret_flags_and_assignments() {
+        let args = [
+            "--token",
+            "abc",
+            "--max-tokens=5",
+            "API_KEY=abc",
+            "--verbose",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            redact_args(&args),
+            [
+                "--token",
+                "***",
+                "--max-tokens=5",
+                "API_KEY=***",
+                "--verbose"
+            ]
+        );
+    }
+}